@@ -0,0 +1,46 @@
+use crate::{
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct ArrayExpression {
+    elements: Vec<Box<dyn Expression>>,
+}
+
+impl ArrayExpression {
+    pub fn boxed(elements: Vec<Box<dyn Expression>>) -> Box<Self> {
+        Box::new(Self { elements })
+    }
+}
+
+impl IrNode for ArrayExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}ArrayExpression\n", indent_str);
+        for element in self.elements.iter() {
+            output += &element.dump(indent + 1);
+        }
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let elements = self
+            .elements
+            .iter_mut()
+            .map(|element| element.evaluate(interpreter))
+            .collect::<Result<Vec<_>, Exception>>()?;
+
+        Ok(interpreter.new_array(elements))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        for element in self.elements.iter() {
+            element.emit_bytecode(compiler)?;
+        }
+
+        compiler.emit(Instruction::MakeArray(self.elements.len()));
+        Ok(())
+    }
+}
@@ -1,33 +1,75 @@
 use crate::{
-    ir::{IrNode, marker::Expression},
-    runtime::{exception::*, Interpreter, Value},
+    ir::{marker::Expression, ops::BinaryOperator, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Expression, Clone)]
 pub struct AssignmentExpression {
     lhs: Box<dyn Expression>,
+    /// The operator of a compound assignment like `+=`, which combines the current value with
+    /// the right-hand side
+    op: Option<BinaryOperator>,
     rhs: Box<dyn Expression>,
 }
 
 impl AssignmentExpression {
-    pub fn boxed(lhs: Box<dyn Expression>, rhs: Box<dyn Expression>) -> Box<Self> {
-        Box::new(Self { lhs, rhs })
+    pub fn boxed(
+        lhs: Box<dyn Expression>,
+        op: Option<BinaryOperator>,
+        rhs: Box<dyn Expression>,
+    ) -> Box<Self> {
+        Box::new(Self { lhs, op, rhs })
     }
 }
 
 impl IrNode for AssignmentExpression {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
-        let mut output = format!("{}AssignmentExpression: \n", indent_str);
+        let op = self
+            .op
+            .as_ref()
+            .map(|op| op.to_string())
+            .unwrap_or_default();
+        let mut output = format!("{}AssignmentExpression: {}=\n", indent_str, op);
         output += &self.lhs.dump(indent + 1);
         output += &self.rhs.dump(indent + 1);
         output
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        let value = self.rhs.evaluate(interpreter)?;
+        let op = match &self.op {
+            Some(op) => op,
+            None => {
+                let rhs = &mut self.rhs;
+                return self
+                    .lhs
+                    .assign_with(interpreter, &mut |interpreter| rhs.evaluate(interpreter));
+            }
+        };
 
-        self.lhs.assign(interpreter, value)
+        // The target is located and read before the right-hand side is evaluated
+        let rhs = &mut self.rhs;
+        self.lhs.update(interpreter, &mut |interpreter, value| {
+            let rhs_val = rhs.evaluate(interpreter)?;
+            op.apply(interpreter, value, rhs_val)
+        })
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let op = match &self.op {
+            Some(op) => op,
+            None => {
+                return self.lhs.emit_assignment_with(compiler, &mut |compiler| {
+                    self.rhs.emit_bytecode(compiler)
+                });
+            }
+        };
+
+        self.lhs.emit_update(compiler, &mut |compiler, _| {
+            self.rhs.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Binary(op.clone()));
+            Ok(())
+        })
     }
 }
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+use crate::{
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, class::ClassDefinition, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct ClassExpression {
+    definition: Rc<ClassDefinition>,
+    superclass: Option<Box<dyn Expression>>,
+}
+
+impl ClassExpression {
+    pub fn boxed(
+        definition: ClassDefinition,
+        superclass: Option<Box<dyn Expression>>,
+    ) -> Box<Self> {
+        Box::new(Self {
+            definition: Rc::new(definition),
+            superclass,
+        })
+    }
+}
+
+impl IrNode for ClassExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!(
+            "{}ClassExpression: {}\n",
+            indent_str,
+            self.definition.name.as_ref().unwrap_or(&"Anonymous".into())
+        );
+        if let Some(superclass) = self.superclass.as_ref() {
+            output += &superclass.dump(indent + 1);
+        }
+        for member in self
+            .definition
+            .constructor
+            .iter()
+            .chain(self.definition.members.iter())
+        {
+            output += &member.body.dump(indent + 1);
+        }
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let parent = self
+            .superclass
+            .as_mut()
+            .map(|superclass| superclass.evaluate(interpreter))
+            .transpose()?;
+        let environment = interpreter.environment();
+        self.definition.create(interpreter, parent, environment)
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        // The methods are compiled when first called, but anything in them that can't be
+        // compiled has to be found now
+        for member in self
+            .definition
+            .constructor
+            .iter()
+            .chain(self.definition.members.iter())
+        {
+            Compiler::compile_function(&member.body)?;
+        }
+
+        if let Some(superclass) = self.superclass.as_ref() {
+            superclass.emit_bytecode(compiler)?;
+        }
+        let class = compiler.class(Rc::clone(&self.definition));
+        compiler.emit(Instruction::MakeClass(class));
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, conversions::to_boolean, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct ConditionalExpression {
+    test: Box<dyn Expression>,
+    consequent: Box<dyn Expression>,
+    alternate: Box<dyn Expression>,
+}
+
+impl ConditionalExpression {
+    pub fn boxed(
+        test: Box<dyn Expression>,
+        consequent: Box<dyn Expression>,
+        alternate: Box<dyn Expression>,
+    ) -> Box<Self> {
+        Box::new(Self {
+            test,
+            consequent,
+            alternate,
+        })
+    }
+}
+
+impl IrNode for ConditionalExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}ConditionalExpression\n", indent_str);
+        output += &self.test.dump(indent + 1);
+        output += &self.consequent.dump(indent + 1);
+        output += &self.alternate.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        match to_boolean(&self.test.evaluate(interpreter)?) {
+            true => self.consequent.evaluate(interpreter),
+            false => self.alternate.evaluate(interpreter),
+        }
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.test.emit_bytecode(compiler)?;
+        let to_alternate = compiler.emit(Instruction::JumpIfFalse(0));
+        self.consequent.emit_bytecode(compiler)?;
+        let to_end = compiler.emit(Instruction::Jump(0));
+        compiler.patch_jump(to_alternate);
+        self.alternate.emit_bytecode(compiler)?;
+        compiler.patch_jump(to_end);
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     ir::{marker::Expression, IrNode},
-    runtime::{Exception, Interpreter, Value},
+    runtime::{bytecode::*, Exception, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -22,4 +22,9 @@ impl IrNode for EmptyExpression {
     fn evaluate(&mut self, _: &mut Interpreter) -> Result<Value, Exception> {
         Ok(Value::Undefined)
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     ir::{marker::Expression, IrNode},
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -29,4 +29,10 @@ impl IrNode for Literal {
     fn evaluate(&mut self, _interpreter: &mut Interpreter) -> Result<Value, Exception> {
         Ok(self.val.clone())
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let constant = compiler.constant(self.val.clone());
+        compiler.emit(Instruction::Constant(constant));
+        Ok(())
+    }
 }
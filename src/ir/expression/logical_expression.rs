@@ -0,0 +1,49 @@
+use crate::{
+    ir::{marker::Expression, ops::LogicalOperator, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct LogicalExpression {
+    op: LogicalOperator,
+    lhs: Box<dyn Expression>,
+    rhs: Box<dyn Expression>,
+}
+
+impl LogicalExpression {
+    pub fn boxed(
+        op: LogicalOperator,
+        lhs: Box<dyn Expression>,
+        rhs: Box<dyn Expression>,
+    ) -> Box<Self> {
+        Box::new(Self { op, lhs, rhs })
+    }
+}
+
+impl IrNode for LogicalExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}LogicalExpression: {}\n", indent_str, self.op);
+        output += &self.lhs.dump(indent + 1);
+        output += &self.rhs.dump(indent + 1);
+        output
+    }
+
+    // The result is whichever operand decided it, not a boolean
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let lhs_val = self.lhs.evaluate(interpreter)?;
+        match self.op.short_circuits(&lhs_val) {
+            true => Ok(lhs_val),
+            false => self.rhs.evaluate(interpreter),
+        }
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.lhs.emit_bytecode(compiler)?;
+        let to_end = compiler.emit(Instruction::ShortCircuit(self.op.clone(), 0));
+        self.rhs.emit_bytecode(compiler)?;
+        compiler.patch_jump(to_end);
+        Ok(())
+    }
+}
@@ -1,56 +1,218 @@
 use crate::{
-    ir::{IrNode, marker::Expression},
-    runtime::{exception::*, Interpreter, Value},
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
+/// How a member expression names the property it accesses.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub enum Property {
+    /// `object.name`
+    Named(String),
+    /// `object[expression]`, the key is whatever the expression evaluates to
+    Computed(Box<dyn Expression>),
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Expression, Clone)]
 pub struct MemberExpression {
-    object: String,
-    property: String,
+    object: Box<dyn Expression>,
+    property: Property,
 }
 
 impl MemberExpression {
-    pub fn boxed(object: String, property: String) -> Box<Self> {
+    pub fn boxed(object: Box<dyn Expression>, property: Property) -> Box<Self> {
         Box::new(Self { object, property })
     }
+
+    /// Evaluates the object, and then the name of the property this expression accesses.
+    fn object_and_key(
+        &mut self,
+        interpreter: &mut Interpreter,
+    ) -> Result<(Value, String), Exception> {
+        let object = self.object.evaluate(interpreter)?;
+        let key = match &mut self.property {
+            Property::Named(name) => name.clone(),
+            Property::Computed(expression) => expression.evaluate(interpreter)?.property_key(),
+        };
+        Ok((object, key))
+    }
+
+    fn emit_object_and_key(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.object.emit_bytecode(compiler)?;
+        if let Property::Computed(expression) = &self.property {
+            expression.emit_bytecode(compiler)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assigns `value` to the property `key` of `object`, evaluating to it. Primitives have no
+/// properties of their own to assign to, so it is dropped for them.
+fn put_value(
+    interpreter: &mut Interpreter,
+    object: &Value,
+    key: &str,
+    value: Value,
+) -> Result<Value, Exception> {
+    match object {
+        Value::Object(o) => interpreter.set_property(o, key, value.clone())?,
+        Value::Undefined | Value::Null => bail!(TypeError("Variable is not an object".to_owned())),
+        _ => (),
+    }
+    Ok(value)
 }
 
 impl IrNode for MemberExpression {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
-        format!("{}MemberExpression: {}\n", indent_str, self.property)
+        let mut output = match &self.property {
+            Property::Named(name) => format!("{}MemberExpression: {}\n", indent_str, name),
+            Property::Computed(_) => format!("{}MemberExpression: computed\n", indent_str),
+        };
+        output += &self.object.dump(indent + 1);
+        if let Property::Computed(expression) = &self.property {
+            output += &expression.dump(indent + 1);
+        }
+        output
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        interpreter.inspect_variable(&self.object, |obj| {
-            let object = match obj {
-                Value::Object(o) => o,
-                _ => return Err(TypeError("Expected object".to_owned())),
-            };
-            let property = {
-                object
-                    .borrow()
-                    .get(&self.property)
-                    .cloned()
-                    .ok_or_else(|| ReferenceError(self.property.clone()))?
-            };
-
-            Ok(property)
-        })
+        // The same lookup, only the object isn't needed afterwards
+        let (value, _) = self.evaluate_callee(interpreter)?;
+        Ok(value)
+    }
+
+    fn evaluate_callee(
+        &mut self,
+        interpreter: &mut Interpreter,
+    ) -> Result<(Value, Value), Exception> {
+        let (object, key) = self.object_and_key(interpreter)?;
+        Ok((interpreter.get_value_property(&object, &key)?, object))
     }
 
     fn assign(&mut self, interpreter: &mut Interpreter, value: Value) -> Result<Value, Exception> {
-        interpreter.edit_variable(&self.object, |obj| match obj {
-            Value::Object(o) => {
-                let mut obj_borrow = o.borrow_mut();
-                let prop = obj_borrow
-                    .get_mut(&self.property)
-                    .ok_or_else(|| ReferenceError(self.property.clone()))?;
-                *prop = value.clone();
-                Ok(value)
+        self.assign_with(interpreter, &mut |_| Ok(value.clone()))
+    }
+
+    fn assign_with(
+        &mut self,
+        interpreter: &mut Interpreter,
+        value: &mut dyn FnMut(&mut Interpreter) -> Result<Value, Exception>,
+    ) -> Result<Value, Exception> {
+        let (object, key) = self.object_and_key(interpreter)?;
+        let value = value(interpreter)?;
+        put_value(interpreter, &object, &key, value)
+    }
+
+    fn update(
+        &mut self,
+        interpreter: &mut Interpreter,
+        update: &mut dyn FnMut(&mut Interpreter, Value) -> Result<Value, Exception>,
+    ) -> Result<Value, Exception> {
+        let (object, key) = self.object_and_key(interpreter)?;
+        let value = interpreter.get_value_property(&object, &key)?;
+        let value = update(interpreter, value)?;
+        put_value(interpreter, &object, &key, value)
+    }
+
+    fn delete(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let (object, key) = self.object_and_key(interpreter)?;
+        let deleted = interpreter.delete_property(&object, &key)?;
+        Ok(Value::Boolean(deleted))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.emit_object_and_key(compiler)?;
+        let instruction = match &self.property {
+            Property::Named(name) => Instruction::GetProperty(compiler.name(name)),
+            Property::Computed(_) => Instruction::GetIndex,
+        };
+        compiler.emit(instruction);
+        Ok(())
+    }
+
+    // The object stays below the method, to be the receiver of the call
+    fn emit_callee(&self, compiler: &mut Compiler) -> Result<bool, CompileError> {
+        self.object.emit_bytecode(compiler)?;
+        match &self.property {
+            Property::Named(name) => {
+                let name = compiler.name(name);
+                compiler.emit(Instruction::GetMethod(name));
+            }
+            Property::Computed(expression) => {
+                compiler.emit(Instruction::Dup);
+                expression.emit_bytecode(compiler)?;
+                compiler.emit(Instruction::GetIndex);
+            }
+        }
+        Ok(true)
+    }
+
+    fn emit_assignment(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.emit_object_and_key(compiler)?;
+        let instruction = match &self.property {
+            Property::Named(name) => Instruction::SetProperty(compiler.name(name)),
+            Property::Computed(_) => Instruction::SetIndex,
+        };
+        compiler.emit(instruction);
+        Ok(())
+    }
+
+    // The value is pushed after the object and key, and buried below them to be assigned
+    fn emit_assignment_with(
+        &self,
+        compiler: &mut Compiler,
+        emit_value: &mut dyn FnMut(&mut Compiler) -> Result<(), CompileError>,
+    ) -> Result<(), CompileError> {
+        self.emit_object_and_key(compiler)?;
+        emit_value(compiler)?;
+        let (instruction, depth) = match &self.property {
+            Property::Named(name) => (Instruction::SetProperty(compiler.name(name)), 1),
+            Property::Computed(_) => (Instruction::SetIndex, 2),
+        };
+        compiler.emit(Instruction::Bury(depth));
+        compiler.emit(instruction);
+        Ok(())
+    }
+
+    // The object, and the key of a computed property, stay below the value being updated
+    fn emit_update(
+        &self,
+        compiler: &mut Compiler,
+        emit_update: &mut dyn FnMut(&mut Compiler, usize) -> Result<(), CompileError>,
+    ) -> Result<(), CompileError> {
+        self.object.emit_bytecode(compiler)?;
+        let (get, set, depth) = match &self.property {
+            Property::Named(name) => {
+                let name = compiler.name(name);
+                compiler.emit(Instruction::Dup);
+                (
+                    Instruction::GetProperty(name),
+                    Instruction::SetProperty(name),
+                    1,
+                )
+            }
+            Property::Computed(expression) => {
+                expression.emit_bytecode(compiler)?;
+                compiler.emit(Instruction::Dup2);
+                (Instruction::GetIndex, Instruction::SetIndex, 2)
             }
-            _ => Err(TypeError("Variable is not an object".to_owned())),
-        })
+        };
+        compiler.emit(get);
+        emit_update(compiler, depth)?;
+        compiler.emit(Instruction::Bury(depth));
+        compiler.emit(set);
+        Ok(())
+    }
+
+    fn emit_delete(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.emit_object_and_key(compiler)?;
+        let instruction = match &self.property {
+            Property::Named(name) => Instruction::DeleteProperty(compiler.name(name)),
+            Property::Computed(_) => Instruction::DeleteIndex,
+        };
+        compiler.emit(instruction);
+        Ok(())
     }
 }
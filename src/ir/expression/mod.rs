@@ -1,19 +1,33 @@
+pub use array_expression::ArrayExpression;
 pub use assignment_expression::AssignmentExpression;
 pub use binary_expression::BinaryExpression;
 pub use call_expression::CallExpression;
+pub use class_expression::ClassExpression;
+pub use conditional_expression::ConditionalExpression;
+pub use empty::EmptyExpression;
 pub use literal::Literal;
-pub use member_expression::MemberExpression;
+pub use logical_expression::LogicalExpression;
+pub use member_expression::{MemberExpression, Property};
+pub use new_expression::NewExpression;
+pub use object_expression::ObjectExpression;
+pub use super_expression::SuperExpression;
+pub use unary_expression::UnaryExpression;
 pub use update_expression::UpdateExpression;
 pub use variable::Variable;
-pub use empty::EmptyExpression;
-pub use object_expression::ObjectExpression;
 
+mod array_expression;
 mod assignment_expression;
 mod binary_expression;
 mod call_expression;
+mod class_expression;
+mod conditional_expression;
+mod empty;
 mod literal;
+mod logical_expression;
 mod member_expression;
+mod new_expression;
+mod object_expression;
+mod super_expression;
+mod unary_expression;
 mod update_expression;
 mod variable;
-mod empty;
-mod object_expression;
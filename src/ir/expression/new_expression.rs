@@ -0,0 +1,62 @@
+use crate::{
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct NewExpression {
+    callee: Box<dyn Expression>,
+    arguments: Vec<Box<dyn Expression>>,
+}
+
+impl NewExpression {
+    pub fn boxed(callee: Box<dyn Expression>, arguments: Vec<Box<dyn Expression>>) -> Box<Self> {
+        Box::new(Self { callee, arguments })
+    }
+}
+
+impl IrNode for NewExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}NewExpression\n", indent_str);
+        output += &self.callee.dump(indent + 1);
+        for argument in self.arguments.iter() {
+            output += &argument.dump(indent + 1);
+        }
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let constructor = self.callee.evaluate(interpreter)?;
+        let arguments = self
+            .arguments
+            .iter_mut()
+            .map(|argument| argument.evaluate(interpreter))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Whether it is a constructor, and its prototype, are only looked at once the arguments
+        // are evaluated
+        let instance = interpreter.new_instance(&constructor)?;
+
+        let function = match &constructor {
+            Value::Object(function) => function,
+            _ => unreachable!("new_instance only accepts functions"),
+        };
+
+        // The constructor may return an object to use instead, anything else is ignored
+        match interpreter.call_constructor(function, instance.clone(), arguments)? {
+            value @ Value::Object(_) => Ok(value),
+            _ => Ok(instance),
+        }
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.callee.emit_bytecode(compiler)?;
+        for argument in self.arguments.iter() {
+            argument.emit_bytecode(compiler)?;
+        }
+        compiler.emit(Instruction::Construct(self.arguments.len()));
+        Ok(())
+    }
+}
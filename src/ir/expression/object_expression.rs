@@ -1,11 +1,7 @@
-use std::collections::HashMap;
-
 use crate::{
-    ir::{IrNode, marker::Expression},
-    runtime::{exception::*, Interpreter, Value},
-    util::*,
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Properties, Value},
 };
-use crate::prelude::LiteralObject;
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Expression, Clone)]
@@ -28,20 +24,22 @@ impl IrNode for ObjectExpression {
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        let mut props = HashMap::new();
+        let mut props = Properties::new();
         let keys_and_values = self.keys.iter_mut().zip(self.values.iter_mut());
-
-        // While evaluating the values for the properties, any function declarations should
-        // not put themselves in top level scope
-        // TODO: Is this the best way? Hard to get them there otherwise since main interpreter
-        //  eval loop is blind to what is being evaluated
-        interpreter.suppress_declarations();
         for (key, value_expr) in keys_and_values {
-            let value = value_expr.evaluate(interpreter)?;
-            props.insert(key.clone(), value);
+            props.insert(key.clone(), value_expr.evaluate(interpreter)?);
+        }
+
+        Ok(interpreter.new_object(props))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        for value_expr in self.values.iter() {
+            value_expr.emit_bytecode(compiler)?;
         }
-        interpreter.allow_declarations();
 
-        Ok(Value::Object(LiteralObject::new(props).bundle()))
+        let shape = compiler.shape(self.keys.clone());
+        compiler.emit(Instruction::MakeObject(shape));
+        Ok(())
     }
 }
@@ -0,0 +1,126 @@
+use crate::{
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, class, exception::*, Interpreter, ObjectType, Value},
+};
+
+/// The ways `super` can be used in the methods of a class.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub enum SuperExpression {
+    /// `super(...)`, calls the parent constructor on `this`, which is the result
+    Call(Vec<Box<dyn Expression>>),
+    /// `super.name`
+    Member(String),
+    /// `super.name(...)`, calls the parent's method with the current `this`
+    MethodCall(String, Vec<Box<dyn Expression>>),
+}
+
+impl SuperExpression {
+    pub fn boxed_call(arguments: Vec<Box<dyn Expression>>) -> Box<Self> {
+        Box::new(Self::Call(arguments))
+    }
+
+    pub fn boxed_member(name: &str) -> Box<Self> {
+        Box::new(Self::Member(name.to_owned()))
+    }
+
+    pub fn boxed_method_call(name: &str, arguments: Vec<Box<dyn Expression>>) -> Box<Self> {
+        Box::new(Self::MethodCall(name.to_owned(), arguments))
+    }
+
+    fn arguments(&mut self) -> &mut [Box<dyn Expression>] {
+        match self {
+            Self::Call(arguments) | Self::MethodCall(_, arguments) => arguments,
+            Self::Member(_) => &mut [],
+        }
+    }
+}
+
+fn evaluate_all(
+    arguments: &mut [Box<dyn Expression>],
+    interpreter: &mut Interpreter,
+) -> Result<Vec<Value>, Exception> {
+    arguments
+        .iter_mut()
+        .map(|argument| argument.evaluate(interpreter))
+        .collect()
+}
+
+impl IrNode for SuperExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        match self {
+            Self::Call(_) => format!("{}SuperExpression: call\n", indent_str),
+            Self::Member(name) => format!("{}SuperExpression: {}\n", indent_str, name),
+            Self::MethodCall(name, _) => {
+                format!("{}SuperExpression: call {}\n", indent_str, name)
+            }
+        }
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let home = class::home_object(&interpreter.environment());
+        let this = interpreter.variable("this")?;
+
+        let name = match self {
+            Self::Call(_) => {
+                let constructor = match class::super_constructor(&home)? {
+                    Value::Object(constructor) => constructor,
+                    _ => unreachable!("super_constructor only returns functions"),
+                };
+                let arguments = evaluate_all(self.arguments(), interpreter)?;
+                interpreter.call_constructor(&constructor, this.clone(), arguments)?;
+                return Ok(this);
+            }
+            Self::Member(name) | Self::MethodCall(name, _) => name.clone(),
+        };
+
+        let base = class::super_base(&home)?;
+        let property = interpreter.get_property(&base, &name, this.clone())?;
+        if let Self::Member(_) = self {
+            return Ok(property);
+        }
+
+        match property {
+            Value::Object(method) if method.borrow().get_type() == ObjectType::Function => {
+                let arguments = evaluate_all(self.arguments(), interpreter)?;
+                interpreter.call_function(&method, this, arguments)
+            }
+            _ => Err(TypeError(format!("\'super.{}\' is not callable", name))),
+        }
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let this = compiler.name("this");
+        match self {
+            Self::Call(arguments) => {
+                compiler.emit(Instruction::GetVariable(this));
+                compiler.emit(Instruction::SuperConstructor);
+                for argument in arguments.iter() {
+                    argument.emit_bytecode(compiler)?;
+                }
+                compiler.emit(Instruction::CallSuper(arguments.len()));
+                compiler.emit(Instruction::Pop);
+                compiler.emit(Instruction::GetVariable(this));
+            }
+            Self::Member(name) => {
+                let name = compiler.name(name);
+                compiler.emit(Instruction::GetVariable(this));
+                compiler.emit(Instruction::GetSuper(name));
+            }
+            Self::MethodCall(name, arguments) => {
+                let callee = compiler.name(&format!("super.{}", name));
+                let name = compiler.name(name);
+                // The receiver stays below the method, like for any other method call
+                compiler.emit(Instruction::GetVariable(this));
+                compiler.emit(Instruction::Dup);
+                compiler.emit(Instruction::GetSuper(name));
+                for argument in arguments.iter() {
+                    argument.emit_bytecode(compiler)?;
+                }
+                compiler.emit(Instruction::CallMethod(arguments.len(), callee));
+            }
+        }
+        Ok(())
+    }
+}
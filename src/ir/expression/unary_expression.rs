@@ -0,0 +1,46 @@
+use crate::{
+    ir::{marker::Expression, ops::UnaryOperator, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Expression, Clone)]
+pub struct UnaryExpression {
+    op: UnaryOperator,
+    argument: Box<dyn Expression>,
+}
+
+impl UnaryExpression {
+    pub fn boxed(op: UnaryOperator, argument: Box<dyn Expression>) -> Box<Self> {
+        Box::new(Self { op, argument })
+    }
+}
+
+impl IrNode for UnaryExpression {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}UnaryExpression: {}\n", indent_str, self.op);
+        output += &self.argument.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        // These two need to know what the argument refers to, not only its value
+        let value = match self.op {
+            UnaryOperator::Delete => return self.argument.delete(interpreter),
+            UnaryOperator::TypeOf => self.argument.evaluate_for_typeof(interpreter)?,
+            _ => self.argument.evaluate(interpreter)?,
+        };
+        self.op.apply(interpreter, value)
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        match self.op {
+            UnaryOperator::Delete => return self.argument.emit_delete(compiler),
+            UnaryOperator::TypeOf => self.argument.emit_for_typeof(compiler)?,
+            _ => self.argument.emit_bytecode(compiler)?,
+        }
+        compiler.emit(Instruction::Unary(self.op.clone()));
+        Ok(())
+    }
+}
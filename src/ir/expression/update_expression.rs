@@ -1,20 +1,24 @@
 use crate::{
-    ir::{expression::Variable, marker::Expression, ops::UnaryOperator, IrNode},
-    runtime::{exception::*, Interpreter, Value},
+    ir::{
+        marker::Expression,
+        ops::{BinaryOperator, UnaryOperator, UpdateOperator},
+        IrNode,
+    },
+    runtime::{bytecode::*, conversions::to_number, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Expression, Clone)]
 pub struct UpdateExpression {
-    variable: Variable,
-    op: UnaryOperator,
+    argument: Box<dyn Expression>,
+    op: UpdateOperator,
     prefix: bool,
 }
 
 impl UpdateExpression {
-    pub fn boxed(variable: Variable, op: UnaryOperator, prefix: bool) -> Box<Self> {
+    pub fn boxed(argument: Box<dyn Expression>, op: UpdateOperator, prefix: bool) -> Box<Self> {
         Box::new(Self {
-            variable,
+            argument,
             op,
             prefix,
         })
@@ -24,36 +28,55 @@ impl UpdateExpression {
 impl IrNode for UpdateExpression {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
-        format!(
-            "{}UpdateExpression: {} {}\n",
-            indent_str, self.variable.name, self.op
-        )
+        let position = if self.prefix { "prefix" } else { "postfix" };
+        let mut output = format!("{}UpdateExpression: {} {}\n", indent_str, position, self.op);
+        output += &self.argument.dump(indent + 1);
+        output
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        interpreter.edit_variable(&self.variable.name, |variable| {
-            // If postfix operator, then cache original
-            let original_value = if !self.prefix {
-                variable.clone()
-            } else {
-                Value::Undefined
-            };
+        let op = &self.op;
+        // Postfix evaluates to the original value, converted to a number
+        let mut original = Value::Undefined;
+        let updated = self
+            .argument
+            .update(interpreter, &mut |interpreter, value| {
+                let n = to_number(interpreter, value)?;
+                original = Value::Number(n);
+                Ok(Value::Number(match op {
+                    UpdateOperator::Increment => n + 1f64,
+                    UpdateOperator::Decrement => n - 1f64,
+                }))
+            })?;
 
-            // Apply operation
-            match self.op {
-                UnaryOperator::Increment => match variable {
-                    Value::Number(n) => *n += 1f64,
-                    _ => unimplemented!(),
-                },
-                _ => unimplemented!(),
-            }
+        match self.prefix {
+            true => Ok(updated),
+            false => Ok(original),
+        }
+    }
 
-            // If prefix operator, return current value, otherwise the cached value
-            if self.prefix {
-                success!(variable.clone())
-            } else {
-                success!(original_value)
-            }
-        })
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let one = compiler.constant(Value::Number(1f64));
+        let op = match self.op {
+            UpdateOperator::Increment => BinaryOperator::Plus,
+            UpdateOperator::Decrement => BinaryOperator::Minus,
+        };
+
+        self.argument
+            .emit_update(compiler, &mut |compiler, depth| {
+                compiler.emit(Instruction::Unary(UnaryOperator::Plus));
+                // Postfix keeps a copy of the original under whatever locates the target
+                if !self.prefix {
+                    compiler.emit(Instruction::Dup);
+                    compiler.emit(Instruction::Bury(depth + 1));
+                }
+                compiler.emit(Instruction::Constant(one));
+                compiler.emit(Instruction::Binary(op.clone()));
+                Ok(())
+            })?;
+        if !self.prefix {
+            compiler.emit(Instruction::Pop);
+        }
+        Ok(())
     }
 }
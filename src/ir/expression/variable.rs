@@ -1,6 +1,6 @@
 use crate::{
-    ir::{IrNode, marker::Expression},
-    runtime::{Exception, Interpreter, Value},
+    ir::{marker::Expression, IrNode},
+    runtime::{bytecode::*, Exception, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -37,4 +37,37 @@ impl IrNode for Variable {
     fn assign(&mut self, interpreter: &mut Interpreter, value: Value) -> Result<Value, Exception> {
         interpreter.assign_variable(&self.name, value)
     }
+
+    fn evaluate_for_typeof(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        interpreter.variable_if_declared(&self.name)
+    }
+
+    // Variables aren't properties, they can't be deleted
+    fn delete(&mut self, _interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        Ok(Value::Boolean(false))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let name = compiler.name(&self.name);
+        compiler.emit(Instruction::GetVariable(name));
+        Ok(())
+    }
+
+    fn emit_assignment(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let name = compiler.name(&self.name);
+        compiler.emit(Instruction::SetVariable(name));
+        Ok(())
+    }
+
+    fn emit_for_typeof(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let name = compiler.name(&self.name);
+        compiler.emit(Instruction::GetVariableIfDeclared(name));
+        Ok(())
+    }
+
+    fn emit_delete(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let deleted = compiler.constant(Value::Boolean(false));
+        compiler.emit(Instruction::Constant(deleted));
+        Ok(())
+    }
 }
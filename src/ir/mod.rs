@@ -1,4 +1,7 @@
-use crate::runtime::{Exception, Interpreter, Value};
+use crate::runtime::{
+    bytecode::{CompileError, Compiler, Instruction},
+    Exception, Interpreter, Value,
+};
 
 pub mod expression;
 pub mod marker;
@@ -13,4 +16,110 @@ pub trait IrNode {
     fn assign(&mut self, interpreter: &mut Interpreter, value: Value) -> Result<Value, Exception> {
         Err(Exception::TypeError("Not an lvalue".to_owned()))
     }
+
+    /// Assigns this node the value `value` evaluates to, which runs after whatever locates the
+    /// target, like the object and key of a property, has been evaluated.
+    fn assign_with(
+        &mut self,
+        interpreter: &mut Interpreter,
+        value: &mut dyn FnMut(&mut Interpreter) -> Result<Value, Exception>,
+    ) -> Result<Value, Exception> {
+        let value = value(interpreter)?;
+        self.assign(interpreter, value)
+    }
+
+    /// Assigns this node the value `update` computes from its current one, and evaluates to it.
+    /// Whatever locates the target, like the key of a computed property, is only evaluated once.
+    fn update(
+        &mut self,
+        interpreter: &mut Interpreter,
+        update: &mut dyn FnMut(&mut Interpreter, Value) -> Result<Value, Exception>,
+    ) -> Result<Value, Exception> {
+        let value = self.evaluate(interpreter)?;
+        let value = update(interpreter, value)?;
+        self.assign(interpreter, value)
+    }
+
+    /// Evaluates this node as the callee of a call, along with the value the call binds `this`
+    /// to, which is the object a method is read from and undefined for anything else.
+    fn evaluate_callee(
+        &mut self,
+        interpreter: &mut Interpreter,
+    ) -> Result<(Value, Value), Exception> {
+        Ok((self.evaluate(interpreter)?, Value::Undefined))
+    }
+
+    /// Evaluates this node as the operand of `typeof`, which is undefined rather than an error
+    /// for a variable that isn't declared.
+    fn evaluate_for_typeof(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        self.evaluate(interpreter)
+    }
+
+    /// Deletes what this node refers to, evaluating to whether it is gone. Only properties can
+    /// be deleted, anything else is evaluated for its side effects and stays.
+    fn delete(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        self.evaluate(interpreter)?;
+        Ok(Value::Boolean(true))
+    }
+
+    /// Emits the instructions for this node, which leave its value on the stack.
+    #[allow(unused_variables)]
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let dump = self.dump(0);
+        let node = dump.lines().next().unwrap_or_default().trim();
+        Err(CompileError::Unsupported(node.to_owned()))
+    }
+
+    /// Emits the instructions that assign the value on top of the stack to this node, leaving
+    /// the value there.
+    #[allow(unused_variables)]
+    fn emit_assignment(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        Err(CompileError::Invalid("Not an lvalue".to_owned()))
+    }
+
+    /// Emits the instructions of [`IrNode::assign_with`], leaving the assigned value on the
+    /// stack. `emit_value` emits the instructions that push the value.
+    fn emit_assignment_with(
+        &self,
+        compiler: &mut Compiler,
+        emit_value: &mut dyn FnMut(&mut Compiler) -> Result<(), CompileError>,
+    ) -> Result<(), CompileError> {
+        emit_value(compiler)?;
+        self.emit_assignment(compiler)
+    }
+
+    /// Emits the instructions of [`IrNode::update`], leaving the assigned value on the stack.
+    /// `emit_update` emits the instructions that replace the current value on top of the stack
+    /// with the new one, and is told how many values locating the target are kept below it.
+    fn emit_update(
+        &self,
+        compiler: &mut Compiler,
+        emit_update: &mut dyn FnMut(&mut Compiler, usize) -> Result<(), CompileError>,
+    ) -> Result<(), CompileError> {
+        self.emit_bytecode(compiler)?;
+        emit_update(compiler, 0)?;
+        self.emit_assignment(compiler)
+    }
+
+    /// Emits the instructions of [`IrNode::evaluate_callee`], which leave the callee on the
+    /// stack. Returns whether they also leave the value for `this` below it.
+    fn emit_callee(&self, compiler: &mut Compiler) -> Result<bool, CompileError> {
+        self.emit_bytecode(compiler)?;
+        Ok(false)
+    }
+
+    /// Emits the instructions of [`IrNode::evaluate_for_typeof`].
+    fn emit_for_typeof(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.emit_bytecode(compiler)
+    }
+
+    /// Emits the instructions of [`IrNode::delete`], which leave whether it succeeded on the
+    /// stack.
+    fn emit_delete(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        let deleted = compiler.constant(Value::Boolean(true));
+        compiler.emit(Instruction::Constant(deleted));
+        Ok(())
+    }
 }
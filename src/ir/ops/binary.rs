@@ -1,6 +1,10 @@
 use std::fmt;
 
-use resast::BinaryOp;
+use resast::{AssignOp, BinaryOp};
+
+use std::rc::Rc;
+
+use crate::runtime::{conversions::*, exception::*, Interpreter, ObjectType, Value};
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Clone)]
@@ -17,8 +21,16 @@ pub enum BinaryOperator {
     Minus,
     Times,
     Over,
-    Or,
-    And,
+    Modulo,
+    Exponent,
+    LeftShift,
+    RightShift,
+    UnsignedRightShift,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    In,
+    InstanceOf,
 }
 
 impl fmt::Display for BinaryOperator {
@@ -36,8 +48,16 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::GreaterThanEqual => f.write_str(">="),
             BinaryOperator::Times => f.write_str("*"),
             BinaryOperator::Over => f.write_str("/"),
-            BinaryOperator::Or => f.write_str("||"),
-            BinaryOperator::And => f.write_str("&&"),
+            BinaryOperator::Modulo => f.write_str("%"),
+            BinaryOperator::Exponent => f.write_str("**"),
+            BinaryOperator::LeftShift => f.write_str("<<"),
+            BinaryOperator::RightShift => f.write_str(">>"),
+            BinaryOperator::UnsignedRightShift => f.write_str(">>>"),
+            BinaryOperator::BitwiseOr => f.write_str("|"),
+            BinaryOperator::BitwiseXor => f.write_str("^"),
+            BinaryOperator::BitwiseAnd => f.write_str("&"),
+            BinaryOperator::In => f.write_str("in"),
+            BinaryOperator::InstanceOf => f.write_str("instanceof"),
         }
     }
 }
@@ -57,9 +77,167 @@ impl From<resast::BinaryOp> for BinaryOperator {
             BinaryOp::Minus => Self::Minus,
             BinaryOp::Times => Self::Times,
             BinaryOp::Over => Self::Over,
-            BinaryOp::Or => Self::Or,
-            BinaryOp::And => Self::And,
-            _ => unimplemented!(),
+            BinaryOp::Mod => Self::Modulo,
+            BinaryOp::PowerOf => Self::Exponent,
+            BinaryOp::LeftShift => Self::LeftShift,
+            BinaryOp::RightShift => Self::RightShift,
+            BinaryOp::UnsignedRightShift => Self::UnsignedRightShift,
+            BinaryOp::Or => Self::BitwiseOr,
+            BinaryOp::XOr => Self::BitwiseXor,
+            BinaryOp::And => Self::BitwiseAnd,
+            BinaryOp::In => Self::In,
+            BinaryOp::InstanceOf => Self::InstanceOf,
+        }
+    }
+}
+
+impl BinaryOperator {
+    /// The operator a compound assignment like `+=` applies, `=` has none.
+    pub fn from_assignment(assign_op: AssignOp) -> Option<Self> {
+        Some(match assign_op {
+            AssignOp::Equal => return None,
+            AssignOp::PlusEqual => Self::Plus,
+            AssignOp::MinusEqual => Self::Minus,
+            AssignOp::TimesEqual => Self::Times,
+            AssignOp::DivEqual => Self::Over,
+            AssignOp::ModEqual => Self::Modulo,
+            AssignOp::PowerOfEqual => Self::Exponent,
+            AssignOp::LeftShiftEqual => Self::LeftShift,
+            AssignOp::RightShiftEqual => Self::RightShift,
+            AssignOp::UnsignedRightShiftEqual => Self::UnsignedRightShift,
+            AssignOp::OrEqual => Self::BitwiseOr,
+            AssignOp::XOrEqual => Self::BitwiseXor,
+            AssignOp::AndEqual => Self::BitwiseAnd,
+        })
+    }
+
+    /// Applies the operator to two already evaluated operands, converting them as needed.
+    pub fn apply(
+        &self,
+        interpreter: &mut Interpreter,
+        lhs_val: Value,
+        rhs_val: Value,
+    ) -> Result<Value, Exception> {
+        use BinaryOperator::*;
+        let val = match self {
+            InstanceOf => return instance_of(&lhs_val, &rhs_val),
+            In => return has_property(&lhs_val, &rhs_val),
+            StrictEqual => Value::Boolean(strict_equals(&lhs_val, &rhs_val)),
+            StrictNotEqual => Value::Boolean(!strict_equals(&lhs_val, &rhs_val)),
+            Equal => Value::Boolean(abstract_equals(interpreter, lhs_val, rhs_val)?),
+            NotEqual => Value::Boolean(!abstract_equals(interpreter, lhs_val, rhs_val)?),
+            // Either operand being a string makes this a concatenation
+            Plus => {
+                let lhs = to_primitive(interpreter, lhs_val, Hint::Default)?;
+                let rhs = to_primitive(interpreter, rhs_val, Hint::Default)?;
+                match (&lhs, &rhs) {
+                    (Value::StringLiteral(_), _) | (_, Value::StringLiteral(_)) => {
+                        let lhs = to_string(interpreter, lhs)?;
+                        let rhs = to_string(interpreter, rhs)?;
+                        Value::StringLiteral(lhs + &rhs)
+                    }
+                    _ => Value::Number(to_number(interpreter, lhs)? + to_number(interpreter, rhs)?),
+                }
+            }
+            Minus | Times | Over | Modulo | Exponent => {
+                let lhs = to_number(interpreter, lhs_val)?;
+                let rhs = to_number(interpreter, rhs_val)?;
+                Value::Number(match self {
+                    Minus => lhs - rhs,
+                    Times => lhs * rhs,
+                    Over => lhs / rhs,
+                    // Rust's remainder also takes the sign of the dividend
+                    Modulo => lhs % rhs,
+                    _ => exponent(lhs, rhs),
+                })
+            }
+            // Shift counts only use their lowest five bits
+            LeftShift | RightShift => {
+                let lhs = to_int32(interpreter, lhs_val)?;
+                let rhs = to_uint32(interpreter, rhs_val)? & 31;
+                Value::Number(match self {
+                    LeftShift => lhs.wrapping_shl(rhs),
+                    _ => lhs >> rhs,
+                } as f64)
+            }
+            UnsignedRightShift => {
+                let lhs = to_uint32(interpreter, lhs_val)?;
+                let rhs = to_uint32(interpreter, rhs_val)? & 31;
+                Value::Number((lhs >> rhs) as f64)
+            }
+            BitwiseOr | BitwiseXor | BitwiseAnd => {
+                let lhs = to_int32(interpreter, lhs_val)?;
+                let rhs = to_int32(interpreter, rhs_val)?;
+                Value::Number(match self {
+                    BitwiseOr => lhs | rhs,
+                    BitwiseXor => lhs ^ rhs,
+                    _ => lhs & rhs,
+                } as f64)
+            }
+            // Comparisons involving NaN are always false, `a <= b` too even though it's `!(b < a)`
+            LessThan | GreaterThan | LessThanEqual | GreaterThanEqual => {
+                let lhs = to_primitive(interpreter, lhs_val, Hint::Number)?;
+                let rhs = to_primitive(interpreter, rhs_val, Hint::Number)?;
+                Value::Boolean(match self {
+                    LessThan => less_than(interpreter, lhs, rhs)? == Some(true),
+                    GreaterThan => less_than(interpreter, rhs, lhs)? == Some(true),
+                    LessThanEqual => less_than(interpreter, rhs, lhs)? == Some(false),
+                    _ => less_than(interpreter, lhs, rhs)? == Some(false),
+                })
+            }
+        };
+        Ok(val)
+    }
+}
+
+/// `**`, which differs from `powf` in that a base of one or minus one doesn't make an infinite
+/// or NaN exponent a number.
+fn exponent(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1f64 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+    base.powf(exponent)
+}
+
+/// Whether `object` has a property named `key`, either its own or an inherited one.
+fn has_property(key: &Value, object: &Value) -> Result<Value, Exception> {
+    let key = key.property_key();
+    match object {
+        Value::Object(o) => Ok(Value::Boolean(o.borrow().lookup(&key).is_some())),
+        _ => bail!(TypeError(format!(
+            "Cannot use 'in' operator to search for '{}' in {}",
+            key, object
+        ))),
+    }
+}
+
+/// Whether `constructor.prototype` is anywhere along the prototype chain of `value`.
+fn instance_of(value: &Value, constructor: &Value) -> Result<Value, Exception> {
+    let prototype = match constructor {
+        Value::Object(o) if o.borrow().get_type() == ObjectType::Function => {
+            o.borrow().lookup("prototype")
+        }
+        _ => bail!(TypeError(
+            "Right-hand side of \'instanceof\' is not callable".to_owned()
+        )),
+    };
+    let prototype = match prototype {
+        Some(Value::Object(prototype)) => prototype,
+        _ => bail!(TypeError(
+            "Function has non-object prototype in instanceof check".to_owned()
+        )),
+    };
+
+    // Primitives don't inherit from anything
+    let mut current = match value {
+        Value::Object(o) => o.borrow().prototype(),
+        _ => None,
+    };
+    while let Some(link) = current {
+        if Rc::ptr_eq(&link, &prototype) {
+            return Ok(Value::Boolean(true));
         }
+        current = link.borrow().prototype();
     }
+    Ok(Value::Boolean(false))
 }
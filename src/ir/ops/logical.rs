@@ -0,0 +1,60 @@
+use std::fmt;
+
+use resast::LogicalOp;
+
+use crate::runtime::{conversions::to_boolean, Value};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub enum LogicalOperator {
+    And,
+    Or,
+    Coalesce,
+}
+
+impl fmt::Display for LogicalOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicalOperator::And => f.write_str("&&"),
+            LogicalOperator::Or => f.write_str("||"),
+            LogicalOperator::Coalesce => f.write_str("??"),
+        }
+    }
+}
+
+impl From<resast::LogicalOp> for LogicalOperator {
+    fn from(logical_op: LogicalOp) -> Self {
+        match logical_op {
+            LogicalOp::And => Self::And,
+            LogicalOp::Or => Self::Or,
+        }
+    }
+}
+
+impl LogicalOperator {
+    /// Whether the left operand decides the result on its own, in which case it is the result
+    /// and the right operand isn't evaluated at all.
+    pub fn short_circuits(&self, lhs: &Value) -> bool {
+        match self {
+            LogicalOperator::And => !to_boolean(lhs),
+            LogicalOperator::Or => to_boolean(lhs),
+            LogicalOperator::Coalesce => !matches!(lhs, Value::Undefined | Value::Null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The parser doesn't know `??` yet, so it can't be covered from JavaScript
+    #[test]
+    fn coalesce_only_skips_null_and_undefined() {
+        let coalesce = LogicalOperator::Coalesce;
+        assert!(!coalesce.short_circuits(&Value::Null));
+        assert!(!coalesce.short_circuits(&Value::Undefined));
+        assert!(coalesce.short_circuits(&Value::Number(0f64)));
+        assert!(coalesce.short_circuits(&Value::StringLiteral(String::new())));
+        assert!(coalesce.short_circuits(&Value::Boolean(false)));
+    }
+}
@@ -1,4 +1,8 @@
 mod binary;
+mod logical;
 mod unary;
+mod update;
 pub use binary::BinaryOperator;
+pub use logical::LogicalOperator;
 pub use unary::UnaryOperator;
+pub use update::UpdateOperator;
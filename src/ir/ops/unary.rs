@@ -2,27 +2,73 @@ use std::fmt;
 
 use resast::UnaryOp;
 
+use crate::runtime::{conversions::*, exception::*, Interpreter, ObjectType, Value};
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Clone)]
 pub enum UnaryOperator {
-    Increment,
-    Decrement,
+    Minus,
+    Plus,
+    Not,
+    BitwiseNot,
+    TypeOf,
+    Void,
+    Delete,
 }
 
 impl fmt::Display for UnaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            UnaryOperator::Increment => f.write_str("++"),
-            UnaryOperator::Decrement => f.write_str("--"),
+            UnaryOperator::Minus => f.write_str("-"),
+            UnaryOperator::Plus => f.write_str("+"),
+            UnaryOperator::Not => f.write_str("!"),
+            UnaryOperator::BitwiseNot => f.write_str("~"),
+            UnaryOperator::TypeOf => f.write_str("typeof"),
+            UnaryOperator::Void => f.write_str("void"),
+            UnaryOperator::Delete => f.write_str("delete"),
         }
     }
 }
 
 impl From<resast::UnaryOp> for UnaryOperator {
-    fn from(bin_op: UnaryOp) -> Self {
-        match bin_op {
-            UnaryOp::Plus => Self::Increment,
-            _ => unimplemented!(),
+    fn from(unary_op: UnaryOp) -> Self {
+        match unary_op {
+            UnaryOp::Minus => Self::Minus,
+            UnaryOp::Plus => Self::Plus,
+            UnaryOp::Not => Self::Not,
+            UnaryOp::Tilde => Self::BitwiseNot,
+            UnaryOp::TypeOf => Self::TypeOf,
+            UnaryOp::Void => Self::Void,
+            UnaryOp::Delete => Self::Delete,
         }
     }
 }
+
+impl UnaryOperator {
+    /// Applies the operator to an already evaluated operand. Deleting only does something to
+    /// properties, which are never evaluated first, so a value is left alone.
+    pub fn apply(&self, interpreter: &mut Interpreter, value: Value) -> Result<Value, Exception> {
+        Ok(match self {
+            UnaryOperator::Minus => Value::Number(-to_number(interpreter, value)?),
+            UnaryOperator::Plus => Value::Number(to_number(interpreter, value)?),
+            UnaryOperator::Not => Value::Boolean(!to_boolean(&value)),
+            UnaryOperator::BitwiseNot => Value::Number(!to_int32(interpreter, value)? as f64),
+            UnaryOperator::TypeOf => Value::StringLiteral(type_of(&value).to_owned()),
+            UnaryOperator::Void => Value::Undefined,
+            UnaryOperator::Delete => Value::Boolean(true),
+        })
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Number(_) => "number",
+        Value::Undefined => "undefined",
+        // A long standing mistake of the language
+        Value::Null => "object",
+        Value::Boolean(_) => "boolean",
+        Value::StringLiteral(_) => "string",
+        Value::Object(o) if o.borrow().get_type() == ObjectType::Function => "function",
+        Value::Object(_) => "object",
+    }
+}
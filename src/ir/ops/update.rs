@@ -0,0 +1,28 @@
+use std::fmt;
+
+use resast::UpdateOp;
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub enum UpdateOperator {
+    Increment,
+    Decrement,
+}
+
+impl fmt::Display for UpdateOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateOperator::Increment => f.write_str("++"),
+            UpdateOperator::Decrement => f.write_str("--"),
+        }
+    }
+}
+
+impl From<resast::UpdateOp> for UpdateOperator {
+    fn from(update_op: UpdateOp) -> Self {
+        match update_op {
+            UpdateOp::Increment => Self::Increment,
+            UpdateOp::Decrement => Self::Decrement,
+        }
+    }
+}
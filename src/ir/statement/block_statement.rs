@@ -1,6 +1,6 @@
 use crate::{
-    ir::{IrNode, marker::Statement},
-    runtime::{exception::*, Interpreter, Value},
+    ir::{marker::Statement, statement::FunctionExpression, IrNode},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -22,6 +22,16 @@ impl std::fmt::Display for BlockType {
 pub struct Block {
     pub children: Vec<Box<dyn Statement>>,
     scope_type: BlockType,
+    // The names declared with `var` anywhere in a function or the program, not only in this
+    // block, they are bound to undefined before it runs
+    pub var_names: Vec<String>,
+    // The names declared with `let`, `const` or `class` in this block itself, which are bound
+    // when it starts running, but can't be used until their declaration runs
+    pub lexical_names: Vec<String>,
+    // The functions declared in this block itself, which are created and bound when it starts
+    // running, so they can be called before their declaration. Those of a nested block are
+    // assigned to the `var` of the same name then too
+    pub functions: Vec<FunctionExpression>,
 }
 
 impl Block {
@@ -29,6 +39,9 @@ impl Block {
         Self {
             children: Vec::new(),
             scope_type,
+            var_names: Vec::new(),
+            lexical_names: Vec::new(),
+            functions: Vec::new(),
         }
     }
 
@@ -53,6 +66,9 @@ impl IrNode for Block {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
         let mut output = format!("{}{}\n", indent_str, self.scope_type);
+        for function in self.functions.iter() {
+            output += &function.dump(indent + 1);
+        }
         for child in self.children.iter() {
             output += &child.dump(indent + 1);
         }
@@ -62,4 +78,56 @@ impl IrNode for Block {
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
         interpreter.evaluate_scope(self.clone())
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        // Function and global blocks run in the scope their frame was created with
+        let scoped = self.scope_type == BlockType::Control;
+        if scoped {
+            compiler.enter_scope();
+        }
+
+        // Bind what the block declares before any of it runs, top level vars are properties of
+        // the global object
+        for name in self.var_names.iter() {
+            let name = compiler.name(name);
+            match self.scope_type {
+                BlockType::Global => compiler.emit(Instruction::HoistGlobal(name)),
+                _ => compiler.emit(Instruction::HoistVar(name)),
+            };
+        }
+        for name in self.lexical_names.iter() {
+            let name = compiler.name(name);
+            compiler.emit(Instruction::HoistLexical(name));
+        }
+        for function in self.functions.iter() {
+            function.emit_bytecode(compiler)?;
+            let name = compiler.name(function.name().unwrap_or_default());
+            if self.scope_type == BlockType::Global {
+                compiler.emit(Instruction::SetGlobal(name));
+                compiler.emit(Instruction::Pop);
+            } else {
+                if self.scope_type == BlockType::Control {
+                    compiler.emit(Instruction::SetVar(name));
+                }
+                compiler.emit(Instruction::DeclareVariable(name));
+            }
+        }
+
+        if self.children.is_empty() {
+            compiler.emit(Instruction::Undefined);
+        }
+
+        // Only the value of the last child is the value of the block
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                compiler.emit(Instruction::Pop);
+            }
+            child.emit_bytecode(compiler)?;
+        }
+
+        if scoped {
+            compiler.leave_scope();
+        }
+        Ok(())
+    }
 }
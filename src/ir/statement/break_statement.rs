@@ -1,27 +1,39 @@
 use crate::{
     ir::{marker::Statement, IrNode},
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Completion, Interpreter, Value},
 };
 
-// TODO: Support labeled breaks
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Statement, Clone)]
-pub struct BreakStatement;
+pub struct BreakStatement {
+    label: Option<String>,
+}
 
 impl BreakStatement {
-    pub fn boxed() -> Box<Self> {
-        Box::new(Self)
+    pub fn boxed(label: Option<String>) -> Box<Self> {
+        Box::new(Self { label })
     }
 }
 
 impl IrNode for BreakStatement {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
-        format!("{}BreakStatement\n", indent_str)
+        format!(
+            "{}BreakStatement: {}\n",
+            indent_str,
+            self.label.as_deref().unwrap_or("")
+        )
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        interpreter.notify_break();
+        interpreter.complete(Completion::Break(self.label.clone()));
         success!()
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.emit_break(self.label.as_deref())?;
+        // Unreachable, but every node leaves a value
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
 }
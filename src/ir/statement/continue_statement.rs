@@ -0,0 +1,39 @@
+use crate::{
+    ir::{marker::Statement, IrNode},
+    runtime::{bytecode::*, exception::*, Completion, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct ContinueStatement {
+    label: Option<String>,
+}
+
+impl ContinueStatement {
+    pub fn boxed(label: Option<String>) -> Box<Self> {
+        Box::new(Self { label })
+    }
+}
+
+impl IrNode for ContinueStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        format!(
+            "{}ContinueStatement: {}\n",
+            indent_str,
+            self.label.as_deref().unwrap_or("")
+        )
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        interpreter.complete(Completion::Continue(self.label.clone()));
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.emit_continue(self.label.as_deref())?;
+        // Unreachable, but every node leaves a value
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use super::while_statement::loop_test;
+use crate::{
+    ir::{
+        marker::{Expression, Statement},
+        IrNode,
+    },
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct DoWhileStatement {
+    body: Box<dyn Statement>,
+    test: Box<dyn Expression>,
+    // The labels a continue can name to target this loop
+    labels: Vec<String>,
+}
+
+impl DoWhileStatement {
+    pub fn boxed(
+        body: Box<dyn Statement>,
+        test: Box<dyn Expression>,
+        labels: Vec<String>,
+    ) -> Box<Self> {
+        Box::new(Self { body, test, labels })
+    }
+}
+
+impl IrNode for DoWhileStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}DoWhileStatement\n", indent_str);
+        output += &self.body.dump(indent + 1);
+        output += &self.test.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        loop {
+            self.body.evaluate(interpreter)?;
+            if interpreter.should_exit_loop(&self.labels) {
+                break;
+            }
+            if !loop_test(&mut self.test, interpreter)? {
+                break;
+            }
+        }
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.begin_loop(&self.labels);
+        let loop_start = compiler.position();
+
+        // The body runs once before the test is first checked
+        self.body.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        compiler.patch_continues();
+
+        self.test.emit_bytecode(compiler)?;
+        let exit = compiler.emit(Instruction::JumpIfFalse(0));
+        compiler.emit(Instruction::Jump(loop_start));
+
+        compiler.patch_jump(exit);
+        compiler.end_loop();
+
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
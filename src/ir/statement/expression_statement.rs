@@ -3,7 +3,7 @@ use crate::{
         marker::{Expression, Statement},
         IrNode,
     },
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -33,4 +33,8 @@ impl IrNode for ExpressionStatement {
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
         self.expr.evaluate(interpreter)
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.expr.emit_bytecode(compiler)
+    }
 }
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir::{
+        marker::{Expression, Statement},
+        statement::DeclarationKind,
+        IrNode,
+    },
+    runtime::{bytecode::*, exception::*, for_in_keys, Interpreter, Value},
+};
+
+/// Whether a loop goes over the keys of an object, or the values an iterable produces.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ForInKind {
+    In,
+    Of,
+}
+
+/// What each key or value of a for-in or for-of loop is assigned to.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub enum LoopVariable {
+    /// A `let` or `const` declaration, bound anew in a scope of its own for every iteration
+    Lexical(DeclarationKind, String),
+    /// A `var` declaration, bound along with the other vars of the function and assigned by
+    /// every iteration
+    Var(String),
+    /// An existing variable or property
+    Target(Box<dyn Expression>),
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct ForInStatement {
+    kind: ForInKind,
+    variable: LoopVariable,
+    iterable: Box<dyn Expression>,
+    body: Box<dyn Statement>,
+    // The labels a continue can name to target this loop
+    labels: Vec<String>,
+}
+
+impl ForInStatement {
+    pub fn boxed(
+        kind: ForInKind,
+        variable: LoopVariable,
+        iterable: Box<dyn Expression>,
+        body: Box<dyn Statement>,
+        labels: Vec<String>,
+    ) -> Box<Self> {
+        Box::new(Self {
+            kind,
+            variable,
+            iterable,
+            body,
+            labels,
+        })
+    }
+
+    // Binds `value` to the loop variable and runs the body once
+    fn iterate(&mut self, interpreter: &mut Interpreter, value: Value) -> Result<(), Exception> {
+        match &mut self.variable {
+            LoopVariable::Lexical(kind, name) => {
+                let (kind, name, body) = (*kind, name.clone(), &mut self.body);
+                interpreter.run_in_scope(HashMap::new(), |interpreter| {
+                    match kind {
+                        DeclarationKind::Const => interpreter.add_constant(name, value),
+                        _ => interpreter.add_variable(name, value),
+                    }
+                    body.evaluate(interpreter)
+                })?;
+                return Ok(());
+            }
+            LoopVariable::Var(name) => {
+                interpreter.assign_variable(name, value)?;
+            }
+            LoopVariable::Target(target) => {
+                target.assign(interpreter, value)?;
+            }
+        }
+        self.body.evaluate(interpreter)?;
+        Ok(())
+    }
+}
+
+impl IrNode for ForInStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let keyword = match self.kind {
+            ForInKind::In => "in",
+            ForInKind::Of => "of",
+        };
+        let mut output = format!("{}ForInStatement: {}\n", indent_str, keyword);
+        match &self.variable {
+            LoopVariable::Lexical(_, name) | LoopVariable::Var(name) => {
+                output += &format!("{}{}\n", crate::util::make_indent(indent + 1), name);
+            }
+            LoopVariable::Target(target) => output += &target.dump(indent + 1),
+        }
+        output += &self.iterable.dump(indent + 1);
+        output += &self.body.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let iterable = self.iterable.evaluate(interpreter)?;
+
+        match self.kind {
+            // The keys are taken up front, properties added while looping aren't visited
+            ForInKind::In => {
+                for key in for_in_keys(&iterable) {
+                    self.iterate(interpreter, Value::StringLiteral(key))?;
+                    if interpreter.should_exit_loop(&self.labels) {
+                        break;
+                    }
+                }
+            }
+            ForInKind::Of => {
+                let iterator = interpreter.get_iterator(iterable)?;
+                while let Some(value) = interpreter.iterator_step(&iterator)? {
+                    self.iterate(interpreter, value)?;
+                    if interpreter.should_exit_loop(&self.labels) {
+                        break;
+                    }
+                }
+            }
+        }
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        // Both loops keep an iterator on the stack, for-in one over the keys
+        self.iterable.emit_bytecode(compiler)?;
+        match self.kind {
+            ForInKind::In => {
+                compiler.emit(Instruction::EnumerateKeys);
+            }
+            ForInKind::Of => {
+                compiler.emit(Instruction::GetIterator);
+                let method = compiler.name("iterable[Symbol.iterator]");
+                compiler.emit(Instruction::CallMethod(0, method));
+            }
+        }
+        compiler.hold_value();
+        compiler.begin_loop(&self.labels);
+        let loop_start = compiler.position();
+
+        let next = compiler.name("next");
+        let callee = compiler.name("iterator.next");
+        compiler.emit(Instruction::Dup);
+        compiler.emit(Instruction::GetMethod(next));
+        compiler.emit(Instruction::CallMethod(0, callee));
+        let exit = compiler.emit(Instruction::IteratorValue(0));
+
+        match &self.variable {
+            LoopVariable::Lexical(kind, name) => {
+                compiler.enter_scope();
+                let name = compiler.name(name);
+                match kind {
+                    DeclarationKind::Const => compiler.emit(Instruction::DeclareConstant(name)),
+                    _ => compiler.emit(Instruction::DeclareVariable(name)),
+                };
+            }
+            LoopVariable::Var(name) => {
+                let name = compiler.name(name);
+                compiler.emit(Instruction::SetVariable(name));
+                compiler.emit(Instruction::Pop);
+            }
+            LoopVariable::Target(target) => {
+                target.emit_assignment(compiler)?;
+                compiler.emit(Instruction::Pop);
+            }
+        }
+
+        self.body.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        if let LoopVariable::Lexical(..) = &self.variable {
+            compiler.leave_scope();
+        }
+        compiler.patch_continues();
+        compiler.emit(Instruction::Jump(loop_start));
+
+        compiler.patch_jump(exit);
+        compiler.end_loop();
+
+        // Done with the iterator
+        compiler.release_value();
+        compiler.emit(Instruction::Pop);
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
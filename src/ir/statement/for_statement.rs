@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
+use super::while_statement::loop_test;
 use crate::{
     ir::{
-        IrNode,
         marker::{Expression, Statement},
+        IrNode,
     },
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -15,6 +18,8 @@ pub struct ForStatement {
     test: Option<Box<dyn Expression>>,
     update: Option<Box<dyn Expression>>,
     body: Box<dyn Statement>,
+    // The labels a continue can name to target this loop
+    labels: Vec<String>,
 }
 
 impl ForStatement {
@@ -24,19 +29,65 @@ impl ForStatement {
         test: Option<Box<dyn Expression>>,
         update: Option<Box<dyn Expression>>,
         body: Box<dyn Statement>,
+        labels: Vec<String>,
     ) -> Box<Self> {
-        assert!(
-            (initializer_expr.is_none() || initializer_decl.is_none())
-                && !(initializer_expr.is_none() && initializer_decl.is_none())
-        );
+        // The initializer is optional, but can't be both an expression and a declaration
+        assert!(initializer_expr.is_none() || initializer_decl.is_none());
         Box::new(Self {
             initializer_expr,
             initializer_decl,
             test,
             update,
             body,
+            labels,
         })
     }
+
+    fn run(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        if self.initializer_expr.is_some() {
+            self.initializer_expr
+                .as_mut()
+                .unwrap_or(
+                    &mut (crate::ir::expression::EmptyExpression::boxed() as Box<dyn Expression>),
+                )
+                .evaluate(interpreter)?;
+        }
+
+        if self.initializer_decl.is_some() {
+            self.initializer_decl
+                .as_mut()
+                .unwrap()
+                .evaluate(interpreter)?;
+        }
+
+        // Each iteration gets its own copy of the bindings declared in the loop head, as the
+        // spec's CreatePerIterationEnvironment does
+        let scoped = self.initializer_decl.is_some();
+        if scoped {
+            interpreter.copy_scope();
+        }
+
+        loop {
+            if let Some(test) = self.test.as_mut() {
+                if !loop_test(test, interpreter)? {
+                    break;
+                }
+            }
+
+            self.body.evaluate(interpreter)?;
+            if interpreter.should_exit_loop(&self.labels) {
+                break;
+            }
+
+            if scoped {
+                interpreter.copy_scope();
+            }
+            if self.update.is_some() {
+                self.update.as_mut().unwrap().evaluate(interpreter)?;
+            }
+        }
+        success!()
+    }
 }
 
 impl IrNode for ForStatement {
@@ -60,42 +111,64 @@ impl IrNode for ForStatement {
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        if self.initializer_expr.is_some() {
-            self.initializer_expr
-                .as_mut()
-                .unwrap_or(
-                    &mut (crate::ir::expression::EmptyExpression::boxed() as Box<dyn Expression>),
-                )
-                .evaluate(interpreter)?;
+        // A declaration in the initializer belongs to the loop, not the enclosing block
+        match self.initializer_decl.is_some() {
+            true => interpreter.run_in_scope(HashMap::new(), |interpreter| self.run(interpreter)),
+            false => self.run(interpreter),
         }
+    }
 
-        if self.initializer_decl.is_some() {
-            self.initializer_decl
-                .as_mut()
-                .unwrap()
-                .evaluate(interpreter)?;
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let scoped = self.initializer_decl.is_some();
+        if scoped {
+            compiler.enter_scope();
         }
 
-        if let Some(test) = self.test.as_mut() {
-            while test.evaluate(interpreter) == Ok(Value::Boolean(true)) {
-                self.body.evaluate(interpreter)?;
+        if let Some(initializer) = self.initializer_expr.as_ref() {
+            initializer.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+        }
 
-                if interpreter.broke() {
-                    interpreter.clear_break();
-                    break;
-                }
+        if let Some(initializer) = self.initializer_decl.as_ref() {
+            initializer.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+            compiler.emit(Instruction::CopyScope);
+        }
 
-                if interpreter.returned() {
-                    break;
-                }
+        compiler.begin_loop(&self.labels);
+        let loop_start = compiler.position();
 
-                if self.update.is_some() {
-                    self.update.as_mut().unwrap().evaluate(interpreter)?;
-                }
+        let exit = match self.test.as_ref() {
+            Some(test) => {
+                test.emit_bytecode(compiler)?;
+                Some(compiler.emit(Instruction::JumpIfFalse(0)))
             }
-        } else {
-            unimplemented!("For loops without tests not supported")
+            None => None,
+        };
+
+        self.body.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        compiler.patch_continues();
+        if scoped {
+            compiler.emit(Instruction::CopyScope);
         }
-        success!()
+
+        if let Some(update) = self.update.as_ref() {
+            update.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+        }
+
+        compiler.emit(Instruction::Jump(loop_start));
+
+        if let Some(exit) = exit {
+            compiler.patch_jump(exit);
+        }
+        compiler.end_loop();
+
+        if scoped {
+            compiler.leave_scope();
+        }
+        compiler.emit(Instruction::Undefined);
+        Ok(())
     }
 }
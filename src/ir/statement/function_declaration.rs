@@ -1,7 +1,6 @@
 use crate::{
-    ir::{IrNode, marker::Expression, statement::Block},
-    runtime::{exception::*, Function, Interpreter, Value},
-    util::*,
+    ir::{marker::Expression, statement::Block, IrNode},
+    runtime::{bytecode::*, exception::*, Function, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -10,6 +9,7 @@ pub struct FunctionExpression {
     name: Option<String>,
     parameters: Vec<String>,
     body: Block,
+    is_arrow: bool,
 }
 
 impl FunctionExpression {
@@ -18,6 +18,7 @@ impl FunctionExpression {
             name,
             parameters,
             body,
+            is_arrow: false,
         }
     }
 
@@ -26,34 +27,67 @@ impl FunctionExpression {
             name,
             parameters,
             body,
+            is_arrow: false,
         })
     }
+
+    /// An arrow function, an expression body is a block that returns it.
+    pub fn boxed_arrow(parameters: Vec<String>, body: Block) -> Box<Self> {
+        Box::new(Self {
+            name: None,
+            parameters,
+            body,
+            is_arrow: true,
+        })
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
 }
 
 impl IrNode for FunctionExpression {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
+        let kind = if self.is_arrow {
+            "ArrowFunction"
+        } else {
+            "FunctionDeclaration"
+        };
         let mut output = format!(
-            "{}FunctionDeclaration: {} | {:?}\n",
-            indent_str, self.name.as_ref().unwrap_or(&"Anonymous".into()), self.parameters
+            "{}{}: {} | {:?}\n",
+            indent_str,
+            kind,
+            self.name.as_ref().unwrap_or(&"Anonymous".into()),
+            self.parameters
         );
         output += &self.body.dump(indent + 1);
         output
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        let function = Function::new(
-            self.name.clone(),
-            self.parameters.clone(),
-            self.body.clone(),
-        );
-
-        let function_wrapped = wrap_object(function);
-
-        if !interpreter.should_suppress_declarations() {
-            interpreter.set_global_property(self.name.as_ref().unwrap(), Value::Object(function_wrapped.clone()));
+        let parameters = self.parameters.clone();
+        let body = self.body.clone();
+        // Arrow functions can't be constructors, so they get no `prototype` object
+        if self.is_arrow {
+            let mut function = Function::arrow(parameters, body);
+            function.environment = Some(interpreter.environment());
+            return Ok(interpreter.new_method(function));
         }
 
-        Ok(Value::Object(function_wrapped))
+        let mut function = Function::new(self.name.clone(), parameters, body);
+        function.environment = Some(interpreter.environment());
+        Ok(interpreter.new_function(function))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let function = compiler.function(
+            self.name.clone(),
+            self.parameters.clone(),
+            &self.body,
+            self.is_arrow,
+        )?;
+        compiler.emit(Instruction::MakeFunction(function));
+        Ok(())
     }
 }
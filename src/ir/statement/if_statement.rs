@@ -3,7 +3,7 @@ use crate::{
         marker::{Expression, Statement},
         IrNode,
     },
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, conversions::to_boolean, exception::*, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -52,22 +52,30 @@ impl IrNode for IfStatement {
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        if let Ok(val) = self.test.evaluate(interpreter) {
-            let test = match val {
-                Value::Boolean(b) => b,
-                _ => {
-                    return Err(TypeError(
-                        "If statement test must evaluate to boolean".to_string(),
-                    ))
-                }
-            };
-
-            if test {
-                self.consequent.evaluate(interpreter)?;
-            } else if let Some(alternate) = self.alternate.as_mut() {
-                alternate.evaluate(interpreter)?;
-            }
+        if to_boolean(&self.test.evaluate(interpreter)?) {
+            self.consequent.evaluate(interpreter)?;
+        } else if let Some(alternate) = self.alternate.as_mut() {
+            alternate.evaluate(interpreter)?;
         }
         success!()
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.test.emit_bytecode(compiler)?;
+        let to_alternate = compiler.emit(Instruction::JumpIfFalse(0));
+
+        self.consequent.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        let to_end = compiler.emit(Instruction::Jump(0));
+
+        compiler.patch_jump(to_alternate);
+        if let Some(alternate) = self.alternate.as_ref() {
+            alternate.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+        }
+
+        compiler.patch_jump(to_end);
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
 }
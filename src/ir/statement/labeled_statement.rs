@@ -0,0 +1,53 @@
+use crate::{
+    ir::{marker::Statement, IrNode},
+    runtime::{bytecode::*, exception::*, Completion, Interpreter, Value},
+};
+
+/// A labeled statement that isn't a loop, which a break naming one of its labels leaves. Labeled
+/// loops keep their labels themselves, since a continue can target them too.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct LabeledStatement {
+    labels: Vec<String>,
+    body: Box<dyn Statement>,
+}
+
+impl LabeledStatement {
+    pub fn boxed(labels: Vec<String>, body: Box<dyn Statement>) -> Box<Self> {
+        Box::new(Self { labels, body })
+    }
+}
+
+impl IrNode for LabeledStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!(
+            "{}LabeledStatement: {}\n",
+            indent_str,
+            self.labels.join(" ")
+        );
+        output += &self.body.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        self.body.evaluate(interpreter)?;
+
+        let completion = interpreter.take_completion();
+        match &completion {
+            Completion::Break(Some(label)) if self.labels.contains(label) => (),
+            _ => interpreter.complete(completion),
+        }
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.begin_labeled(&self.labels);
+        self.body.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        compiler.end_labeled();
+
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
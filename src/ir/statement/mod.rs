@@ -1,17 +1,33 @@
 pub use block_statement::{Block, BlockType};
 pub use break_statement::BreakStatement;
+pub use continue_statement::ContinueStatement;
+pub use do_while_statement::DoWhileStatement;
 pub use expression_statement::ExpressionStatement;
+pub use for_in_statement::{ForInKind, ForInStatement, LoopVariable};
 pub use for_statement::ForStatement;
 pub use function_declaration::FunctionExpression;
 pub use if_statement::IfStatement;
+pub use labeled_statement::LabeledStatement;
 pub use return_statement::ReturnStatement;
-pub use variable_declaration::VariableDeclaration;
+pub use switch_statement::{SwitchCase, SwitchStatement};
+pub use throw_statement::ThrowStatement;
+pub use try_statement::TryStatement;
+pub use variable_declaration::{DeclarationKind, VariableDeclaration};
+pub use while_statement::WhileStatement;
 
 mod block_statement;
 mod break_statement;
+mod continue_statement;
+mod do_while_statement;
 mod expression_statement;
+mod for_in_statement;
 mod for_statement;
 mod function_declaration;
 mod if_statement;
+mod labeled_statement;
 mod return_statement;
+mod switch_statement;
+mod throw_statement;
+mod try_statement;
 mod variable_declaration;
+mod while_statement;
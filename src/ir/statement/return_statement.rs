@@ -1,9 +1,9 @@
 use crate::{
     ir::{
-        IrNode,
         marker::{Expression, Statement},
+        IrNode,
     },
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Completion, Interpreter, Value},
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -28,17 +28,31 @@ impl IrNode for ReturnStatement {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
         let mut output = format!("{}ReturnStatement\n", indent_str);
-        output += &self.expression.as_ref().unwrap().dump(indent + 1);
+        if let Some(expression) = self.expression.as_ref() {
+            output += &expression.dump(indent + 1);
+        }
         output
     }
 
     fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
-        if let Some(mut expr) = self.expression.take() {
-            if let Ok(val) = expr.evaluate(interpreter) {
-                interpreter.set_return_val(val);
+        let value = match self.expression.as_mut() {
+            Some(expression) => expression.evaluate(interpreter)?,
+            None => Value::Undefined,
+        };
+        interpreter.complete(Completion::Return(value));
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        match self.expression.as_ref() {
+            Some(expression) => expression.emit_bytecode(compiler)?,
+            None => {
+                compiler.emit(Instruction::Undefined);
             }
-            interpreter.notify_return();
         }
-        success!()
+        compiler.emit_return()?;
+        // Unreachable, but every node leaves a value
+        compiler.emit(Instruction::Undefined);
+        Ok(())
     }
 }
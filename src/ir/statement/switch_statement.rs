@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir::{
+        marker::{Expression, Statement},
+        ops::BinaryOperator,
+        IrNode,
+    },
+    runtime::{bytecode::*, exception::*, Completion, Interpreter, Value},
+};
+
+/// One `case` of a switch, or its `default` when there is no test.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub struct SwitchCase {
+    pub test: Option<Box<dyn Expression>>,
+    pub body: Vec<Box<dyn Statement>>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct SwitchStatement {
+    discriminant: Box<dyn Expression>,
+    cases: Vec<SwitchCase>,
+}
+
+impl SwitchStatement {
+    pub fn boxed(discriminant: Box<dyn Expression>, cases: Vec<SwitchCase>) -> Box<Self> {
+        Box::new(Self {
+            discriminant,
+            cases,
+        })
+    }
+
+    // The case the switch starts running at, the first one strictly equal to the discriminant,
+    // or the default if none is
+    fn matching_case(
+        &mut self,
+        interpreter: &mut Interpreter,
+        discriminant: &Value,
+    ) -> Result<Option<usize>, Exception> {
+        for (i, case) in self.cases.iter_mut().enumerate() {
+            if let Some(test) = case.test.as_mut() {
+                if test.evaluate(interpreter)? == *discriminant {
+                    return Ok(Some(i));
+                }
+            }
+        }
+        Ok(self.cases.iter().position(|case| case.test.is_none()))
+    }
+}
+
+impl IrNode for SwitchStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}SwitchStatement\n", indent_str);
+        output += &self.discriminant.dump(indent + 1);
+        for case in self.cases.iter() {
+            let case_indent = crate::util::make_indent(indent + 1);
+            match case.test.as_ref() {
+                Some(test) => {
+                    output += &format!("{}Case\n", case_indent);
+                    output += &test.dump(indent + 2);
+                }
+                None => output += &format!("{}Default\n", case_indent),
+            }
+            for statement in case.body.iter() {
+                output += &statement.dump(indent + 2);
+            }
+        }
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let discriminant = self.discriminant.evaluate(interpreter)?;
+        let start = match self.matching_case(interpreter, &discriminant)? {
+            Some(start) => start,
+            None => return success!(),
+        };
+
+        // Every case after the one that matched runs too, until something breaks out
+        let cases = &mut self.cases[start..];
+        interpreter.run_in_scope(HashMap::new(), |interpreter| {
+            for statement in cases.iter_mut().flat_map(|case| case.body.iter_mut()) {
+                statement.evaluate(interpreter)?;
+                if interpreter.is_abrupt() {
+                    break;
+                }
+            }
+            Ok(())
+        })?;
+
+        let completion = interpreter.take_completion();
+        if completion != Completion::Break(None) {
+            interpreter.complete(completion);
+        }
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        // The discriminant stays on the stack, to compare each test against
+        self.discriminant.emit_bytecode(compiler)?;
+        compiler.hold_value();
+        compiler.begin_switch();
+        compiler.enter_scope();
+
+        let mut to_bodies = Vec::new();
+        for case in self.cases.iter() {
+            if let Some(test) = case.test.as_ref() {
+                compiler.emit(Instruction::Dup);
+                test.emit_bytecode(compiler)?;
+                compiler.emit(Instruction::Binary(BinaryOperator::StrictEqual));
+                let to_next = compiler.emit(Instruction::JumpIfFalse(0));
+                to_bodies.push(Some(compiler.emit(Instruction::Jump(0))));
+                compiler.patch_jump(to_next);
+            } else {
+                to_bodies.push(None);
+            }
+        }
+        // Nothing matched, so on to the default, or past every case without one
+        let no_match = compiler.emit(Instruction::Jump(0));
+
+        let mut to_default = Some(no_match);
+        for (case, to_body) in self.cases.iter().zip(to_bodies) {
+            match to_body {
+                Some(jump) => compiler.patch_jump(jump),
+                None => {
+                    if let Some(jump) = to_default.take() {
+                        compiler.patch_jump(jump);
+                    }
+                }
+            }
+            for statement in case.body.iter() {
+                statement.emit_bytecode(compiler)?;
+                compiler.emit(Instruction::Pop);
+            }
+        }
+        if let Some(jump) = to_default {
+            compiler.patch_jump(jump);
+        }
+
+        compiler.leave_scope();
+        compiler.end_switch();
+
+        // Done with the discriminant
+        compiler.release_value();
+        compiler.emit(Instruction::Pop);
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
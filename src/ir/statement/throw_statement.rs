@@ -0,0 +1,45 @@
+use crate::{
+    ir::{
+        marker::{Expression, Statement},
+        IrNode,
+    },
+    runtime::{
+        bytecode::{CompileError, Compiler, Instruction},
+        exception::*,
+        Interpreter, Value,
+    },
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct ThrowStatement {
+    argument: Box<dyn Expression>,
+}
+
+impl ThrowStatement {
+    pub fn boxed(argument: Box<dyn Expression>) -> Box<Self> {
+        Box::new(Self { argument })
+    }
+}
+
+impl IrNode for ThrowStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}ThrowStatement\n", indent_str);
+        output += &self.argument.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let value = self.argument.evaluate(interpreter)?;
+        bail!(Thrown(value))
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        self.argument.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Throw);
+        // Unreachable, but every node leaves a value
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
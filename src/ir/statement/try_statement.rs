@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir::{marker::Statement, statement::Block, IrNode},
+    runtime::{
+        bytecode::{CompileError, Compiler, Instruction},
+        exception::*,
+        Interpreter, Value,
+    },
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct TryStatement {
+    block: Block,
+    // The name the caught value is bound to, a catch clause may leave it out
+    parameter: Option<String>,
+    handler: Option<Block>,
+    finalizer: Option<Block>,
+}
+
+impl TryStatement {
+    pub fn boxed(
+        block: Block,
+        parameter: Option<String>,
+        handler: Option<Block>,
+        finalizer: Option<Block>,
+    ) -> Box<Self> {
+        Box::new(Self {
+            block,
+            parameter,
+            handler,
+            finalizer,
+        })
+    }
+}
+
+impl IrNode for TryStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}TryStatement\n", indent_str);
+        output += &self.block.dump(indent + 1);
+        if let Some(handler) = self.handler.as_ref() {
+            output += &format!(
+                "{}CatchClause: {}\n",
+                crate::util::make_indent(indent + 1),
+                self.parameter.as_deref().unwrap_or("")
+            );
+            output += &handler.dump(indent + 2);
+        }
+        if let Some(finalizer) = self.finalizer.as_ref() {
+            output += &format!("{}Finally\n", crate::util::make_indent(indent + 1));
+            output += &finalizer.dump(indent + 2);
+        }
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        let mut result = self.block.evaluate(interpreter);
+
+        if let Some(handler) = self.handler.as_ref() {
+            if let Err(exception) = result {
+                let mut context = HashMap::new();
+                if let Some(parameter) = self.parameter.as_ref() {
+                    context.insert(parameter.clone(), exception.into_value(interpreter));
+                }
+                result = interpreter.run_with(handler.clone(), context);
+            }
+        }
+
+        if let Some(finalizer) = self.finalizer.as_mut() {
+            // A break, continue or return out of the try or catch block waits for the finally
+            // block
+            let completion = interpreter.take_completion();
+            finalizer.evaluate(interpreter)?;
+
+            // Unless the finally block completes abruptly itself, which wins over everything
+            if interpreter.is_abrupt() {
+                return success!();
+            }
+            interpreter.complete(completion);
+        }
+
+        result
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let finalizer = self.finalizer.as_ref();
+
+        // An exception thrown in the try block goes to the catch clause, or else the finally block
+        let handler = compiler.emit(Instruction::PushHandler(0));
+        compiler.begin_try(finalizer);
+        self.block.emit_bytecode(compiler)?;
+        compiler.end_try();
+        compiler.emit(Instruction::PopHandler);
+
+        let mut rethrow = None;
+        if let Some(catch) = self.handler.as_ref() {
+            let skip_catch = compiler.emit(Instruction::Jump(0));
+            compiler.patch_jump(handler);
+
+            // With a finally block, an exception thrown in the catch clause has to run it too
+            if finalizer.is_some() {
+                rethrow = Some(compiler.emit(Instruction::PushHandler(0)));
+                compiler.begin_try(finalizer);
+            }
+            compiler.enter_scope();
+            match self.parameter.as_ref() {
+                Some(parameter) => {
+                    let name = compiler.name(parameter);
+                    compiler.emit(Instruction::DeclareVariable(name));
+                }
+                None => {
+                    compiler.emit(Instruction::Pop);
+                }
+            }
+            catch.emit_bytecode(compiler)?;
+            compiler.leave_scope();
+            if finalizer.is_some() {
+                compiler.end_try();
+                compiler.emit(Instruction::PopHandler);
+            }
+
+            compiler.patch_jump(skip_catch);
+        } else {
+            rethrow = Some(handler);
+        }
+
+        if let Some(finalizer) = finalizer {
+            // The value of the try statement waits below while the finally block runs
+            compiler.hold_value();
+            finalizer.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+            let end = compiler.emit(Instruction::Jump(0));
+
+            // Or the exception, which is thrown again unless the finally block jumps elsewhere
+            if let Some(rethrow) = rethrow {
+                compiler.patch_jump(rethrow);
+            }
+            finalizer.emit_bytecode(compiler)?;
+            compiler.emit(Instruction::Pop);
+            compiler.emit(Instruction::Throw);
+            compiler.release_value();
+            compiler.patch_jump(end);
+        }
+        Ok(())
+    }
+}
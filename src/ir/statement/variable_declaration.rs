@@ -3,36 +3,62 @@ use crate::{
         marker::{Expression, Statement},
         IrNode,
     },
-    runtime::{exception::*, Interpreter, Value},
+    runtime::{bytecode::*, exception::*, Interpreter, Value},
 };
 
+/// The keyword a variable is declared with. A `var` belongs to the whole function it is
+/// declared in, `let` and `const` only to their block, and a `const` can't be assigned to.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    Var,
+    Let,
+    Const,
+}
+
+impl std::fmt::Display for DeclarationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeclarationKind::Var => f.write_str("var"),
+            DeclarationKind::Let => f.write_str("let"),
+            DeclarationKind::Const => f.write_str("const"),
+        }
+    }
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Statement, Clone)]
 pub struct VariableDeclaration {
+    pub kind: DeclarationKind,
     pub name: String,
     pub value: Option<Box<dyn Expression>>,
 }
 
 impl VariableDeclaration {
-    pub fn new(name: &str, value: Box<dyn Expression>) -> Self {
+    pub fn new(kind: DeclarationKind, name: &str, value: Option<Box<dyn Expression>>) -> Self {
         Self {
+            kind,
             name: name.to_owned(),
-            value: Some(value),
+            value,
         }
     }
 
-    pub fn boxed(name: &str, value: Box<dyn Expression>) -> Box<Self> {
-        Box::new(Self {
-            name: name.to_owned(),
-            value: Some(value),
-        })
+    pub fn boxed(
+        kind: DeclarationKind,
+        name: &str,
+        value: Option<Box<dyn Expression>>,
+    ) -> Box<Self> {
+        Box::new(Self::new(kind, name, value))
     }
 }
 
 impl IrNode for VariableDeclaration {
     fn dump(&self, indent: u32) -> String {
         let indent_str = crate::util::make_indent(indent);
-        let mut output = format!("{}VariableDeclaration: {}\n", indent_str, self.name);
+        let mut output = format!(
+            "{}VariableDeclaration: {} {}\n",
+            indent_str, self.kind, self.name
+        );
         if let Some(value) = self.value.as_ref() {
             output += &value.dump(indent + 1);
         }
@@ -43,11 +69,49 @@ impl IrNode for VariableDeclaration {
         let value = self
             .value
             .as_mut()
-            .map(|v| v.evaluate(interpreter).unwrap_or_default())
-            .unwrap_or(Value::Undefined);
+            .map(|v| v.evaluate(interpreter))
+            .transpose()?;
 
-        interpreter.add_variable(self.name.clone(), value);
+        match (self.kind, value) {
+            // A var was bound when its function started, so the declaration only assigns to it
+            (DeclarationKind::Var, Some(value)) => {
+                interpreter.assign_variable(&self.name, value)?;
+            }
+            (DeclarationKind::Var, None) => (),
+            (DeclarationKind::Let, value) => {
+                interpreter.add_variable(self.name.clone(), value.unwrap_or_default())
+            }
+            (DeclarationKind::Const, value) => {
+                interpreter.add_constant(self.name.clone(), value.unwrap_or_default())
+            }
+        }
 
         success!()
     }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        let name = compiler.name(&self.name);
+        if self.kind == DeclarationKind::Var {
+            if let Some(value) = self.value.as_ref() {
+                value.emit_bytecode(compiler)?;
+                compiler.emit(Instruction::SetVariable(name));
+                compiler.emit(Instruction::Pop);
+            }
+            compiler.emit(Instruction::Undefined);
+            return Ok(());
+        }
+
+        match self.value.as_ref() {
+            Some(value) => value.emit_bytecode(compiler)?,
+            None => {
+                compiler.emit(Instruction::Undefined);
+            }
+        }
+        match self.kind {
+            DeclarationKind::Const => compiler.emit(Instruction::DeclareConstant(name)),
+            _ => compiler.emit(Instruction::DeclareVariable(name)),
+        };
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
 }
@@ -0,0 +1,73 @@
+use crate::{
+    ir::{
+        marker::{Expression, Statement},
+        IrNode,
+    },
+    runtime::{bytecode::*, conversions::to_boolean, exception::*, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Statement, Clone)]
+pub struct WhileStatement {
+    test: Box<dyn Expression>,
+    body: Box<dyn Statement>,
+    // The labels a continue can name to target this loop
+    labels: Vec<String>,
+}
+
+impl WhileStatement {
+    pub fn boxed(
+        test: Box<dyn Expression>,
+        body: Box<dyn Statement>,
+        labels: Vec<String>,
+    ) -> Box<Self> {
+        Box::new(Self { test, body, labels })
+    }
+}
+
+/// Evaluates the test of a loop, which decides whether to run the body again.
+pub(super) fn loop_test(
+    test: &mut Box<dyn Expression>,
+    interpreter: &mut Interpreter,
+) -> Result<bool, Exception> {
+    Ok(to_boolean(&test.evaluate(interpreter)?))
+}
+
+impl IrNode for WhileStatement {
+    fn dump(&self, indent: u32) -> String {
+        let indent_str = crate::util::make_indent(indent);
+        let mut output = format!("{}WhileStatement\n", indent_str);
+        output += &self.test.dump(indent + 1);
+        output += &self.body.dump(indent + 1);
+        output
+    }
+
+    fn evaluate(&mut self, interpreter: &mut Interpreter) -> Result<Value, Exception> {
+        while loop_test(&mut self.test, interpreter)? {
+            self.body.evaluate(interpreter)?;
+            if interpreter.should_exit_loop(&self.labels) {
+                break;
+            }
+        }
+        success!()
+    }
+
+    fn emit_bytecode(&self, compiler: &mut Compiler) -> Result<(), CompileError> {
+        compiler.begin_loop(&self.labels);
+        let loop_start = compiler.position();
+
+        self.test.emit_bytecode(compiler)?;
+        let exit = compiler.emit(Instruction::JumpIfFalse(0));
+
+        self.body.emit_bytecode(compiler)?;
+        compiler.emit(Instruction::Pop);
+        compiler.patch_continues();
+        compiler.emit(Instruction::Jump(loop_start));
+
+        compiler.patch_jump(exit);
+        compiler.end_loop();
+
+        compiler.emit(Instruction::Undefined);
+        Ok(())
+    }
+}
@@ -4,3 +4,4 @@ pub mod runtime;
 pub mod util;
 pub mod parse;
 pub mod prelude;
+pub mod repl;
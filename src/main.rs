@@ -1,9 +1,23 @@
-use jsrs::prelude::*;
+use std::io::IsTerminal;
+
+use jsrs::{prelude::*, repl::Repl};
 
 fn main() {
+    // Nothing to run and a user at the keyboard, so start a REPL
+    if std::env::args().nth(1).is_none() && std::io::stdin().is_terminal() {
+        Repl::new().run();
+        return;
+    }
+
     let input = get_input();
 
-    let program = parse_program(&input);
+    let program = match parse_program(&input) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     println!("{}", program.print());
 
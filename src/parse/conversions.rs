@@ -1,132 +1,323 @@
-use std::string::ToString;
+use std::convert::TryFrom;
 
 use resast::prelude::*;
 
 use crate::{
-    ir::{expression::*, marker::*, ops::*, statement::*},
-    parse::parser::*,
-    runtime::*,
+    ir::{expression::*, marker::*, ops::BinaryOperator, statement::*},
+    parse::{parser::*, ParseError},
+    runtime::{
+        class::{ClassDefinition, ClassMember, MemberKind},
+        *,
+    },
 };
 
-// Helps Rust figure out e.into() when e is in a Box..
-impl From<Box<resast::expr::Expr<'_>>> for Box<dyn Expression> {
-    fn from(expr: Box<Expr<'_>>) -> Self {
-        (*expr).into()
+// Helps Rust figure out e.try_into() when e is in a Box..
+impl TryFrom<Box<resast::expr::Expr<'_>>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(expr: Box<Expr<'_>>) -> Result<Self, Self::Error> {
+        Self::try_from(*expr)
+    }
+}
+
+impl TryFrom<Box<resast::stmt::Stmt<'_>>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(stmt: Box<Stmt<'_>>) -> Result<Self, Self::Error> {
+        Self::try_from(*stmt)
     }
 }
 
-impl From<Box<resast::stmt::Stmt<'_>>> for Box<dyn Statement> {
-    fn from(expr: Box<Stmt<'_>>) -> Self {
-        (*expr).into()
+/// Converts a list of AST expressions, stopping at the first one that fails.
+fn convert_all(exprs: Vec<Expr<'_>>) -> Result<Vec<Box<dyn Expression>>, ParseError> {
+    exprs
+        .into_iter()
+        .map(Box::<dyn Expression>::try_from)
+        .collect()
+}
+
+/// Parses a numeric literal as written in source, including the hex, octal and binary forms.
+fn parse_number(number: &str) -> Result<f64, ParseError> {
+    let with_radix =
+        |digits: &str, radix| i64::from_str_radix(digits, radix).ok().map(|n| n as f64);
+
+    let parsed = match number.get(..2) {
+        Some("0x") | Some("0X") => with_radix(&number[2..], 16),
+        Some("0o") | Some("0O") => with_radix(&number[2..], 8),
+        Some("0b") | Some("0B") => with_radix(&number[2..], 2),
+        _ => number.parse::<f64>().ok(),
+    };
+
+    parsed.ok_or_else(|| {
+        ParseError::invalid(number, &format!("\'{}\' is not a valid number", number))
+    })
+}
+
+/// A human readable name for an expression, used to report unsupported constructs.
+fn describe_expr(expr: &Expr<'_>) -> &'static str {
+    match expr {
+        Expr::Array(_) => "array expression",
+        Expr::ArrowFunc(_) => "arrow function",
+        Expr::ArrowParamPlaceHolder(_, _) => "arrow function parameters",
+        Expr::Assign(_) => "assignment expression",
+        Expr::Await(_) => "await expression",
+        Expr::Binary(_) => "binary expression",
+        Expr::Class(_) => "class expression",
+        Expr::Call(_) => "call expression",
+        Expr::Conditional(_) => "conditional expression",
+        Expr::Func(_) => "function expression",
+        Expr::Ident(_) => "identifier",
+        Expr::Lit(_) => "literal",
+        Expr::Logical(_) => "logical expression",
+        Expr::Member(_) => "member expression",
+        Expr::MetaProp(_) => "meta property",
+        Expr::New(_) => "new expression",
+        Expr::Obj(_) => "object expression",
+        Expr::Sequence(_) => "sequence expression",
+        Expr::Spread(_) => "spread expression",
+        Expr::Super => "super",
+        Expr::TaggedTemplate(_) => "tagged template",
+        Expr::This => "this",
+        Expr::Unary(_) => "unary expression",
+        Expr::Update(_) => "update expression",
+        Expr::Yield(_) => "yield expression",
+    }
+}
+
+/// A human readable name for a statement, used to report unsupported constructs.
+fn describe_stmt(stmt: &Stmt<'_>) -> &'static str {
+    match stmt {
+        Stmt::Expr(_) => "expression statement",
+        Stmt::Block(_) => "block statement",
+        Stmt::Empty => "empty statement",
+        Stmt::Debugger => "debugger statement",
+        Stmt::With(_) => "with statement",
+        Stmt::Return(_) => "return statement",
+        Stmt::Labeled(_) => "labeled statement",
+        Stmt::Break(_) => "break statement",
+        Stmt::Continue(_) => "continue statement",
+        Stmt::If(_) => "if statement",
+        Stmt::Switch(_) => "switch statement",
+        Stmt::Throw(_) => "throw statement",
+        Stmt::Try(_) => "try statement",
+        Stmt::While(_) => "while statement",
+        Stmt::DoWhile(_) => "do-while statement",
+        Stmt::For(_) => "for statement",
+        Stmt::ForIn(_) => "for-in statement",
+        Stmt::ForOf(_) => "for-of statement",
+        Stmt::Var(_) => "var declaration",
+    }
+}
+
+/// The name bound by a plain identifier, anything else is a pattern we can't represent.
+fn ident_name(expr: Expr<'_>, construct: &str) -> Result<String, ParseError> {
+    match expr {
+        Expr::Ident(i) => Ok(i.name.to_string()),
+        other => Err(ParseError::invalid(
+            construct,
+            &format!("expected identifier, found {}", describe_expr(&other)),
+        )),
+    }
+}
+
+/// The source text of an expression, as far as it can be told from the syntax tree, to name it
+/// in error messages. Only what usually leads up to a function is spelled out.
+fn source_text(expr: &Expr<'_>) -> String {
+    match expr {
+        Expr::Ident(i) => i.name.to_string(),
+        Expr::This => String::from("this"),
+        Expr::Super => String::from("super"),
+        Expr::Lit(Lit::Number(n)) => n.to_string(),
+        Expr::Lit(Lit::String(StringLit::Double(s))) => format!("\"{}\"", s),
+        Expr::Lit(Lit::String(StringLit::Single(s))) => format!("'{}'", s),
+        Expr::Member(m) if m.computed => {
+            format!("{}[{}]", source_text(&m.object), source_text(&m.property))
+        }
+        Expr::Member(m) => format!("{}.{}", source_text(&m.object), source_text(&m.property)),
+        Expr::Call(c) => format!("{}(...)", source_text(&c.callee)),
+        _ => String::from("(intermediate value)"),
     }
 }
 
 /* # Expressions # */
 
-impl From<resast::Ident<'_>> for Box<dyn Expression> {
-    fn from(i: Ident<'_>) -> Self {
-        Variable::boxed(&i.name)
+impl TryFrom<resast::Ident<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(i: Ident<'_>) -> Result<Self, Self::Error> {
+        Ok(Variable::boxed(&i.name))
     }
 }
 
-impl From<resast::expr::Lit<'_>> for Box<dyn Expression> {
-    fn from(lit: Lit<'_>) -> Self {
+impl TryFrom<resast::expr::Lit<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(lit: Lit<'_>) -> Result<Self, Self::Error> {
         let value = match lit {
-            Lit::Number(number) => Value::Number(number.parse::<f64>().unwrap()),
+            Lit::Number(number) => Value::Number(parse_number(&number)?),
             Lit::Boolean(boolean) => Value::Boolean(boolean),
             Lit::String(string) => Value::StringLiteral(string.clone_inner().into()),
-            _ => unimplemented!(),
+            Lit::Null => Value::Null,
+            Lit::RegEx(_) => return Err(ParseError::unsupported("regular expression literal")),
+            Lit::Template(_) => return Err(ParseError::unsupported("template literal")),
         };
-        Literal::boxed(value)
+        Ok(Literal::boxed(value))
     }
 }
 
-impl From<resast::expr::BinaryExpr<'_>> for Box<dyn Expression> {
-    fn from(b: BinaryExpr<'_>) -> Self {
-        BinaryExpression::boxed(b.operator.into(), b.left.into(), b.right.into())
+impl TryFrom<resast::expr::BinaryExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(b: BinaryExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(BinaryExpression::boxed(
+            b.operator.into(),
+            b.left.try_into()?,
+            b.right.try_into()?,
+        ))
     }
 }
 
-impl From<resast::expr::CallExpr<'_>> for Box<dyn Expression> {
-    fn from(mut c: CallExpr<'_>) -> Self {
-        let callee = c.callee;
-        let arguments = c.arguments.drain(..).map(|e| e.into()).collect();
-        match *callee {
-            Expr::Ident(i) => CallExpression::boxed(&i.name, arguments),
-            Expr::Member(m) => {
-                let id: String = match *m.object {
-                    Expr::Ident(i) => i.name.into(),
-                    _ => panic!("expected ident"),
-                };
-                let property: String = match *m.property {
-                    Expr::Ident(i) => i.name.into(),
-                    _ => panic!("expected ident"),
-                };
-                CallExpression::boxed_member(&id, &property, arguments)
+impl TryFrom<resast::expr::LogicalExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(l: LogicalExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(LogicalExpression::boxed(
+            l.operator.into(),
+            l.left.try_into()?,
+            l.right.try_into()?,
+        ))
+    }
+}
+
+impl TryFrom<resast::expr::ConditionalExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(c: ConditionalExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(ConditionalExpression::boxed(
+            c.test.try_into()?,
+            c.consequent.try_into()?,
+            c.alternate.try_into()?,
+        ))
+    }
+}
+
+impl TryFrom<resast::expr::UnaryExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(u: UnaryExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(UnaryExpression::boxed(
+            u.operator.into(),
+            u.argument.try_into()?,
+        ))
+    }
+}
+
+impl TryFrom<resast::expr::CallExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(c: CallExpr<'_>) -> Result<Self, Self::Error> {
+        let arguments = convert_all(c.arguments)?;
+        match *c.callee {
+            Expr::Super => Ok(SuperExpression::boxed_call(arguments)),
+            Expr::Member(m) if matches!(*m.object, Expr::Super) => {
+                if m.computed {
+                    return Err(ParseError::unsupported("computed super method call"));
+                }
+                let property = ident_name(*m.property, "super method call")?;
+                Ok(SuperExpression::boxed_method_call(&property, arguments))
             }
-            _ => {
-                dbg!(callee);
-                unimplemented!()
+            callee => {
+                let source = source_text(&callee);
+                Ok(CallExpression::boxed(callee.try_into()?, arguments, source))
             }
         }
     }
 }
 
-impl From<resast::expr::AssignExpr<'_>> for Box<dyn Expression> {
-    fn from(assn_expr: AssignExpr<'_>) -> Self {
-        match assn_expr.operator {
-            AssignOp::Equal => (),
-            _ => unimplemented!(),
-        }
+impl TryFrom<resast::expr::NewExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(n: NewExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(NewExpression::boxed(
+            n.callee.try_into()?,
+            convert_all(n.arguments)?,
+        ))
+    }
+}
 
+impl TryFrom<resast::expr::AssignExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(assn_expr: AssignExpr<'_>) -> Result<Self, Self::Error> {
         // TODO: Support patterns
         let e = match assn_expr.left {
-            AssignLeft::Pat(_) => unimplemented!(),
+            AssignLeft::Pat(_) => return Err(ParseError::unsupported("destructuring assignment")),
             AssignLeft::Expr(e) => e,
         };
 
-        AssignmentExpression::boxed(e.into(), assn_expr.right.into())
+        Ok(AssignmentExpression::boxed(
+            e.try_into()?,
+            BinaryOperator::from_assignment(assn_expr.operator),
+            assn_expr.right.try_into()?,
+        ))
     }
 }
 
-impl From<resast::expr::UpdateExpr<'_>> for Box<dyn Expression> {
-    fn from(up_expr: UpdateExpr<'_>) -> Self {
-        let id = match *up_expr.argument {
-            Expr::Ident(i) => i,
-            _ => unimplemented!(),
-        };
-        let op = match up_expr.operator {
-            UpdateOp::Increment => UnaryOperator::Increment,
-            UpdateOp::Decrement => UnaryOperator::Decrement,
-        };
-        UpdateExpression::boxed(Variable::new(&id.name), op, up_expr.prefix)
+impl TryFrom<resast::expr::UpdateExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(up_expr: UpdateExpr<'_>) -> Result<Self, Self::Error> {
+        Ok(UpdateExpression::boxed(
+            up_expr.argument.try_into()?,
+            up_expr.operator.into(),
+            up_expr.prefix,
+        ))
     }
 }
 
-impl From<resast::expr::MemberExpr<'_>> for Box<dyn Expression> {
-    fn from(mem_expr: MemberExpr<'_>) -> Self {
-        // FIXME: Currently only support plain ident member expressions of depth 1
-        let object = match *mem_expr.object {
-            Expr::Ident(i) => i.name.to_string(),
-            _ => {
-                dbg!(mem_expr.object);
-                unimplemented!()
-            }
-        };
+impl TryFrom<resast::expr::MemberExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
 
-        let property = match *mem_expr.property {
-            Expr::Ident(i) => i.name.to_string(),
-            _ => {
-                dbg!(mem_expr.property);
-                unimplemented!()
+    fn try_from(mem_expr: MemberExpr<'_>) -> Result<Self, Self::Error> {
+        if let Expr::Super = *mem_expr.object {
+            if mem_expr.computed {
+                return Err(ParseError::unsupported("computed super property"));
             }
+            let property = ident_name(*mem_expr.property, "super property")?;
+            return Ok(SuperExpression::boxed_member(&property));
+        }
+
+        let object = mem_expr.object.try_into()?;
+        let property = if mem_expr.computed {
+            Property::Computed(mem_expr.property.try_into()?)
+        } else {
+            Property::Named(ident_name(*mem_expr.property, "member expression")?)
         };
-        MemberExpression::boxed(object, property)
+        Ok(MemberExpression::boxed(object, property))
+    }
+}
+
+impl TryFrom<resast::expr::ArrayExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(array_expr: ArrayExpr<'_>) -> Result<Self, Self::Error> {
+        let elements = array_expr
+            .into_iter()
+            .map(|element| match element {
+                Some(Expr::Spread(_)) => Err(ParseError::unsupported("array spread")),
+                Some(expr) => expr.try_into(),
+                // Holes are read back as undefined
+                None => Ok(Literal::boxed(Value::Undefined) as Box<dyn Expression>),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ArrayExpression::boxed(elements))
     }
 }
 
-impl From<resast::expr::ObjExpr<'_>> for Box<dyn Expression> {
-    fn from(obj_expr: ObjExpr<'_>) -> Self {
+impl TryFrom<resast::expr::ObjExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(obj_expr: ObjExpr<'_>) -> Result<Self, Self::Error> {
         let mut keys = Vec::new();
         let mut values = Vec::new();
         for prop in obj_expr {
@@ -134,181 +325,490 @@ impl From<resast::expr::ObjExpr<'_>> for Box<dyn Expression> {
             let key: String;
             match prop {
                 ObjProp::Prop(prop) => {
-                    if prop.computed || prop.method || prop.short_hand || prop.is_static {
-                        unimplemented!()
+                    if prop.computed {
+                        return Err(ParseError::unsupported("computed property key"));
+                    }
+                    if prop.method {
+                        return Err(ParseError::unsupported("method shorthand"));
+                    }
+                    if prop.short_hand {
+                        return Err(ParseError::unsupported("shorthand property"));
                     }
 
                     match prop.kind {
                         PropKind::Init => {
                             value = match prop.value {
-                                PropValue::Expr(expr) => expr.into(),
-                                PropValue::Pat(_) => unimplemented!(),
-                                PropValue::None => unimplemented!(),
+                                PropValue::Expr(expr) => expr.try_into()?,
+                                _ => return Err(ParseError::unsupported("property pattern")),
                             };
                             key = match prop.key {
-                                PropKey::Expr(e) => match e {
-                                    Expr::Ident(ident) => ident.name.to_string(),
-                                    _ => unimplemented!(),
-                                },
-                                PropKey::Lit(_) => unimplemented!(),
-                                PropKey::Pat(_) => unimplemented!(),
+                                PropKey::Expr(e) => ident_name(e, "property key")?,
+                                PropKey::Lit(_) => {
+                                    return Err(ParseError::unsupported("literal property key"))
+                                }
+                                PropKey::Pat(_) => {
+                                    return Err(ParseError::unsupported("pattern property key"))
+                                }
                             };
                         }
-                        PropKind::Get => unimplemented!(),
-                        PropKind::Set => unimplemented!(),
-                        PropKind::Ctor => unimplemented!(),
-                        PropKind::Method => unimplemented!(),
+                        PropKind::Get => return Err(ParseError::unsupported("getter")),
+                        PropKind::Set => return Err(ParseError::unsupported("setter")),
+                        PropKind::Ctor => return Err(ParseError::unsupported("constructor")),
+                        PropKind::Method => return Err(ParseError::unsupported("method")),
                     }
                 }
-                ObjProp::Spread(_) => unimplemented!(),
+                ObjProp::Spread(_) => return Err(ParseError::unsupported("object spread")),
             }
             keys.push(key);
             values.push(value);
         }
-        ObjectExpression::boxed(keys, values)
+        Ok(ObjectExpression::boxed(keys, values))
     }
 }
 
-impl From<resast::Func<'_>> for Box<dyn Expression> {
-    fn from(f: Func<'_>) -> Self {
-        let mut block = Block::new(BlockType::Function);
-        super::parser::parse_block(f.body.0, &mut block);
-        let params = f
-            .params
-            .iter()
-            .map(|param| match param {
-                FuncArg::Expr(Expr::Ident(id)) => id.name.to_string(),
-                FuncArg::Pat(Pat::Ident(id)) => id.name.to_string(),
-                _ => panic!("Unsupported parameter ident"),
-            })
-            .collect();
-        FunctionExpression::boxed(f.id.map(|i| i.name.to_string()), params, block)
+/// The name, parameters and body of a function. Errors inside the function are said to be in
+/// `location`, or in the function by name if there is none.
+fn function_parts(
+    f: Func<'_>,
+    location: Option<String>,
+) -> Result<(Option<String>, Vec<String>, Block), ParseError> {
+    if f.generator {
+        return Err(ParseError::unsupported("generator function"));
+    }
+    if f.is_async {
+        return Err(ParseError::unsupported("async function"));
     }
+    let name = f.id.map(|i| i.name.to_string());
+    let location = location.unwrap_or_else(|| match &name {
+        Some(name) => format!("function '{}'", name),
+        None => "anonymous function".to_owned(),
+    });
+    let block = function_body(f.body).map_err(|e| e.within(&location))?;
+    let params = parameter_names(f.params).map_err(|e| e.within(&location))?;
+    Ok((name, params, block))
+}
+
+fn function_body(body: FuncBody<'_>) -> Result<Block, ParseError> {
+    let mut block = Block::new(BlockType::Function);
+    block.var_names = var_names(&body.0);
+    parse_block(body.0, &mut block)?;
+    Ok(block)
 }
 
-impl From<resast::expr::Expr<'_>> for Box<dyn Expression> {
-    fn from(expr: Expr<'_>) -> Self {
+fn parameter_names(params: Vec<FuncArg<'_>>) -> Result<Vec<String>, ParseError> {
+    params
+        .into_iter()
+        .map(|param| match param {
+            FuncArg::Expr(Expr::Ident(id)) => Ok(id.name.to_string()),
+            FuncArg::Pat(Pat::Ident(id)) => Ok(id.name.to_string()),
+            _ => Err(ParseError::unsupported("parameter pattern")),
+        })
+        .collect()
+}
+
+impl TryFrom<resast::Func<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(f: Func<'_>) -> Result<Self, Self::Error> {
+        let (name, params, block) = function_parts(f, None)?;
+        Ok(FunctionExpression::boxed(name, params, block))
+    }
+}
+
+impl TryFrom<resast::expr::ArrowFuncExpr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(a: ArrowFuncExpr<'_>) -> Result<Self, Self::Error> {
+        if a.is_async {
+            return Err(ParseError::unsupported("async function"));
+        }
+        let block = match a.body {
+            ArrowFuncBody::FuncBody(body) => function_body(body),
+            ArrowFuncBody::Expr(expr) => expr.try_into().map(|value| {
+                let mut block = Block::new(BlockType::Function);
+                block.push(ReturnStatement::boxed(value));
+                block
+            }),
+        };
+        let block = block.map_err(|e| e.within("arrow function"))?;
+        let params = parameter_names(a.params).map_err(|e| e.within("arrow function"))?;
+        Ok(FunctionExpression::boxed_arrow(params, block))
+    }
+}
+
+/// Converts a method, getter, setter or the constructor in the body of a class.
+fn class_member(prop: Prop<'_>) -> Result<ClassMember, ParseError> {
+    if prop.computed {
+        return Err(ParseError::unsupported("computed class member key"));
+    }
+    let kind = match prop.kind {
+        PropKind::Method | PropKind::Ctor => MemberKind::Method,
+        PropKind::Get => MemberKind::Getter,
+        PropKind::Set => MemberKind::Setter,
+        PropKind::Init => return Err(ParseError::unsupported("class field")),
+    };
+    let key = match prop.key {
+        PropKey::Expr(e) => ident_name(e, "class member key")?,
+        PropKey::Lit(_) => return Err(ParseError::unsupported("literal class member key")),
+        PropKey::Pat(_) => return Err(ParseError::unsupported("pattern class member key")),
+    };
+    let (parameters, body) = match prop.value {
+        PropValue::Expr(Expr::Func(f)) => {
+            let (_, parameters, body) = function_parts(f, Some(format!("method '{}'", key)))?;
+            (parameters, body)
+        }
+        _ => return Err(ParseError::invalid("class member", "expected a method")),
+    };
+    Ok(ClassMember {
+        kind,
+        is_static: prop.is_static,
+        key,
+        parameters,
+        body,
+    })
+}
+
+impl TryFrom<resast::Class<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(class: Class<'_>) -> Result<Self, Self::Error> {
+        let name = class.id.map(|i| i.name.to_string());
+        let location = match &name {
+            Some(name) => format!("class '{}'", name),
+            None => "anonymous class".to_owned(),
+        };
+        let mut constructor = None;
+        let mut members = Vec::new();
+        for prop in class.body.0 {
+            let is_constructor = prop.kind == PropKind::Ctor;
+            let member = class_member(prop).map_err(|e| e.within(&location))?;
+            match is_constructor {
+                true => constructor = Some(member),
+                false => members.push(member),
+            }
+        }
+
+        let superclass = class.super_class.map(|e| e.try_into()).transpose()?;
+        let definition = ClassDefinition {
+            name,
+            has_superclass: superclass.is_some(),
+            constructor,
+            members,
+        };
+        Ok(ClassExpression::boxed(definition, superclass))
+    }
+}
+
+impl TryFrom<resast::expr::Expr<'_>> for Box<dyn Expression> {
+    type Error = ParseError;
+
+    fn try_from(expr: Expr<'_>) -> Result<Self, Self::Error> {
         match expr {
-            Expr::Ident(ident) => ident.into(),
-            Expr::Lit(lit) => lit.into(),
-            Expr::Binary(bin_expr) => bin_expr.into(),
-            Expr::Call(call_expr) => call_expr.into(),
-            Expr::Assign(assn_expr) => assn_expr.into(),
-            Expr::Update(up_expr) => up_expr.into(),
-            Expr::Member(mem_expr) => mem_expr.into(),
-            Expr::Obj(obj_expr) => obj_expr.into(),
-            Expr::Func(func_expr) => func_expr.into(),
-            _ => unimplemented!(),
+            Expr::Ident(ident) => ident.try_into(),
+            // `this` is bound like a variable in every function scope, and the global one
+            Expr::This => Ok(Variable::boxed("this")),
+            Expr::Lit(lit) => lit.try_into(),
+            Expr::Binary(bin_expr) => bin_expr.try_into(),
+            Expr::Call(call_expr) => call_expr.try_into(),
+            Expr::New(new_expr) => new_expr.try_into(),
+            Expr::Assign(assn_expr) => assn_expr.try_into(),
+            Expr::Unary(unary_expr) => unary_expr.try_into(),
+            Expr::Logical(logical_expr) => logical_expr.try_into(),
+            Expr::Conditional(conditional_expr) => conditional_expr.try_into(),
+            Expr::Update(up_expr) => up_expr.try_into(),
+            Expr::Member(mem_expr) => mem_expr.try_into(),
+            Expr::Array(array_expr) => array_expr.try_into(),
+            Expr::Obj(obj_expr) => obj_expr.try_into(),
+            Expr::Func(func_expr) => func_expr.try_into(),
+            Expr::ArrowFunc(arrow_expr) => arrow_expr.try_into(),
+            Expr::Class(class) => class.try_into(),
+            other => Err(ParseError::unsupported(describe_expr(&other))),
         }
     }
 }
 
 /* # Expression to Statement# */
 
-impl From<resast::expr::Expr<'_>> for Box<dyn Statement> {
-    fn from(expr: Expr<'_>) -> Self {
-        ExpressionStatement::boxed(expr.into())
+impl TryFrom<resast::expr::Expr<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(expr: Expr<'_>) -> Result<Self, Self::Error> {
+        Ok(ExpressionStatement::boxed(expr.try_into()?))
     }
 }
 
 /* # Statements # */
 
-impl From<resast::stmt::IfStmt<'_>> for Box<dyn Statement> {
-    fn from(is: IfStmt<'_>) -> Self {
-        let test: Box<dyn Expression> = is.test.into();
-        let consequent_expr: Box<dyn Statement> = is.consequent.into();
+impl TryFrom<resast::stmt::IfStmt<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
 
-        let alternate_expr = is.alternate.map(|e| e.into());
+    fn try_from(is: IfStmt<'_>) -> Result<Self, Self::Error> {
+        let test: Box<dyn Expression> = is.test.try_into()?;
+        let consequent_expr: Box<dyn Statement> = is.consequent.try_into()?;
 
-        IfStatement::boxed(test, consequent_expr, alternate_expr)
+        let alternate_expr = is.alternate.map(|e| e.try_into()).transpose()?;
+
+        Ok(IfStatement::boxed(test, consequent_expr, alternate_expr))
     }
 }
 
-impl From<resast::stmt::ForStmt<'_>> for Box<dyn Statement> {
-    fn from(for_stmt: ForStmt<'_>) -> Self {
-        let test: Option<Box<dyn Expression>> = for_stmt.test.map(|t| t.into());
-        let update: Option<Box<dyn Expression>> = for_stmt.update.map(|u| u.into());
-        let body: Box<dyn Statement> = for_stmt.body.into();
-
-        match for_stmt.init {
-            None => ForStatement::boxed(None, None, test, update, body),
-            Some(init) => match init {
-                LoopInit::Variable(VarKind::Let, decls) => {
-                    assert_eq!(decls.len(), 1);
-                    let init_decl = decls.first().cloned().unwrap().into();
-                    ForStatement::boxed(None, Some(init_decl), test, update, body)
+fn for_statement(
+    for_stmt: ForStmt<'_>,
+    labels: Vec<String>,
+) -> Result<Box<dyn Statement>, ParseError> {
+    let test: Option<Box<dyn Expression>> = for_stmt.test.map(|t| t.try_into()).transpose()?;
+    let update: Option<Box<dyn Expression>> = for_stmt.update.map(|u| u.try_into()).transpose()?;
+    let body: Box<dyn Statement> = for_stmt.body.try_into()?;
+
+    match for_stmt.init {
+        None => Ok(ForStatement::boxed(None, None, test, update, body, labels)),
+        Some(init) => match init {
+            LoopInit::Variable(kind, decls) => {
+                if decls.len() != 1 {
+                    return Err(ParseError::unsupported(
+                        "multiple declarations in a for loop initializer",
+                    ));
                 }
-                LoopInit::Variable(_, _) => {
-                    unimplemented!("Only let expressions supported in for loops")
-                }
-                LoopInit::Expr(e) => ForStatement::boxed(Some(e.into()), None, test, update, body),
-            },
+                let init_decl = parse_var_decl(kind, decls)?.remove(0);
+                Ok(ForStatement::boxed(
+                    None,
+                    Some(init_decl),
+                    test,
+                    update,
+                    body,
+                    labels,
+                ))
+            }
+            LoopInit::Expr(e) => Ok(ForStatement::boxed(
+                Some(e.try_into()?),
+                None,
+                test,
+                update,
+                body,
+                labels,
+            )),
+        },
+    }
+}
+
+/// Converts what a for-in or for-of loop assigns each key or value to.
+fn loop_variable(left: LoopLeft<'_>) -> Result<LoopVariable, ParseError> {
+    match left {
+        LoopLeft::Variable(kind, VarDecl { id, init }) => {
+            let name = match id {
+                Pat::Ident(id) => id.name.to_string(),
+                _ => return Err(ParseError::unsupported("destructuring in a loop variable")),
+            };
+            if init.is_some() {
+                return Err(ParseError::invalid(
+                    "loop variable",
+                    "for-in and for-of loop variables can't have an initializer",
+                ));
+            }
+            match kind {
+                VarKind::Var => Ok(LoopVariable::Var(name)),
+                VarKind::Let => Ok(LoopVariable::Lexical(DeclarationKind::Let, name)),
+                VarKind::Const => Ok(LoopVariable::Lexical(DeclarationKind::Const, name)),
+            }
         }
+        LoopLeft::Pat(Pat::Ident(id)) => Ok(LoopVariable::Target(Variable::boxed(&id.name))),
+        LoopLeft::Pat(_) => Err(ParseError::unsupported("destructuring in a loop variable")),
+        LoopLeft::Expr(e) => Ok(LoopVariable::Target(e.try_into()?)),
     }
 }
 
-impl From<resast::stmt::BlockStmt<'_>> for Box<dyn Statement> {
-    fn from(block_statement: BlockStmt<'_>) -> Self {
+/// Converts a loop, which a labeled continue can target if it has `labels`.
+fn loop_statement(stmt: Stmt<'_>, labels: Vec<String>) -> Result<Box<dyn Statement>, ParseError> {
+    match stmt {
+        Stmt::For(for_stmt) => for_statement(for_stmt, labels),
+        Stmt::While(WhileStmt { test, body }) => Ok(WhileStatement::boxed(
+            test.try_into()?,
+            body.try_into()?,
+            labels,
+        )),
+        Stmt::DoWhile(DoWhileStmt { test, body }) => Ok(DoWhileStatement::boxed(
+            body.try_into()?,
+            test.try_into()?,
+            labels,
+        )),
+        Stmt::ForIn(ForInStmt { left, right, body }) => Ok(ForInStatement::boxed(
+            ForInKind::In,
+            loop_variable(left)?,
+            right.try_into()?,
+            body.try_into()?,
+            labels,
+        )),
+        Stmt::ForOf(ForOfStmt { is_await: true, .. }) => {
+            Err(ParseError::unsupported("for await loop"))
+        }
+        Stmt::ForOf(ForOfStmt {
+            left, right, body, ..
+        }) => Ok(ForInStatement::boxed(
+            ForInKind::Of,
+            loop_variable(left)?,
+            right.try_into()?,
+            body.try_into()?,
+            labels,
+        )),
+        other => Err(ParseError::invalid(
+            "loop",
+            &format!("expected a loop, found {}", describe_stmt(&other)),
+        )),
+    }
+}
+
+/// Converts a labeled statement, collecting the labels of directly nested labeled statements.
+fn labeled_statement(
+    labeled: LabeledStmt<'_>,
+    mut labels: Vec<String>,
+) -> Result<Box<dyn Statement>, ParseError> {
+    labels.push(labeled.label.name.to_string());
+    match *labeled.body {
+        Stmt::Labeled(inner) => labeled_statement(inner, labels),
+        // Loops handle breaks and continues naming their labels themselves
+        body @ (Stmt::For(_)
+        | Stmt::ForIn(_)
+        | Stmt::ForOf(_)
+        | Stmt::While(_)
+        | Stmt::DoWhile(_)) => loop_statement(body, labels),
+        body => Ok(LabeledStatement::boxed(labels, body.try_into()?)),
+    }
+}
+
+impl TryFrom<resast::stmt::BlockStmt<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(block_statement: BlockStmt<'_>) -> Result<Self, Self::Error> {
         let mut body_block = Block::new(BlockType::Control);
-        parse_block(block_statement.0, &mut body_block);
-        Box::new(body_block)
+        parse_block(block_statement.0, &mut body_block)?;
+        Ok(Box::new(body_block))
+    }
+}
+
+impl TryFrom<resast::stmt::SwitchStmt<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(switch_stmt: SwitchStmt<'_>) -> Result<Self, Self::Error> {
+        let cases = switch_stmt
+            .cases
+            .into_iter()
+            .map(|case| {
+                // The statements of every case share the scope of the switch
+                let mut block = Block::new(BlockType::Control);
+                parse_block(case.consequent, &mut block)?;
+                Ok(crate::ir::statement::SwitchCase {
+                    test: case.test.map(|t| t.try_into()).transpose()?,
+                    body: block.children,
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        Ok(SwitchStatement::boxed(
+            switch_stmt.discriminant.try_into()?,
+            cases,
+        ))
+    }
+}
+
+impl TryFrom<resast::stmt::TryStmt<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(try_stmt: TryStmt<'_>) -> Result<Self, Self::Error> {
+        let control_block = |statements: BlockStmt<'_>| {
+            let mut block = Block::new(BlockType::Control);
+            parse_block(statements.0, &mut block).map(|_| block)
+        };
+
+        let block = control_block(try_stmt.block)?;
+        let (parameter, handler) = match try_stmt.handler {
+            None => (None, None),
+            Some(CatchClause { param, body }) => {
+                let parameter = match param {
+                    None => None,
+                    Some(Pat::Ident(id)) => Some(id.name.to_string()),
+                    Some(_) => return Err(ParseError::unsupported("catch parameter pattern")),
+                };
+                (parameter, Some(control_block(body)?))
+            }
+        };
+        let finalizer = try_stmt.finalizer.map(control_block).transpose()?;
+
+        Ok(TryStatement::boxed(block, parameter, handler, finalizer))
     }
 }
 
-impl From<resast::stmt::Stmt<'_>> for Box<dyn Statement> {
-    fn from(stmt: Stmt<'_>) -> Self {
+impl TryFrom<resast::stmt::Stmt<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
+
+    fn try_from(stmt: Stmt<'_>) -> Result<Self, Self::Error> {
         match stmt {
-            Stmt::Expr(expr) => expr.into(),
+            Stmt::Expr(expr) => expr.try_into(),
             Stmt::Return(ret_stmt) => match ret_stmt {
-                None => ReturnStatement::boxed_empty(),
-                Some(e) => ReturnStatement::boxed(e.into()),
-            },
-            Stmt::If(if_stmt) => if_stmt.into(),
-            Stmt::For(for_stmt) => for_stmt.into(),
-            Stmt::Break(break_stmt) => match break_stmt {
-                None => BreakStatement::boxed(),
-                Some(_) => {
-                    unimplemented!("Labeled break statements not supported")
-                }
+                None => Ok(ReturnStatement::boxed_empty()),
+                Some(e) => Ok(ReturnStatement::boxed(e.try_into()?)),
             },
-            Stmt::Block(block_stmt) => block_stmt.into(),
-            _ => {
-                dbg!(stmt);
-                unimplemented!()
+            Stmt::If(if_stmt) => if_stmt.try_into(),
+            loop_stmt @ (Stmt::For(_)
+            | Stmt::ForIn(_)
+            | Stmt::ForOf(_)
+            | Stmt::While(_)
+            | Stmt::DoWhile(_)) => loop_statement(loop_stmt, Vec::new()),
+            Stmt::Labeled(labeled) => labeled_statement(labeled, Vec::new()),
+            Stmt::Break(label) => Ok(BreakStatement::boxed(label.map(|l| l.name.to_string()))),
+            Stmt::Continue(label) => {
+                Ok(ContinueStatement::boxed(label.map(|l| l.name.to_string())))
+            }
+            Stmt::Block(block_stmt) => block_stmt.try_into(),
+            Stmt::Throw(expr) => Ok(ThrowStatement::boxed(expr.try_into()?)),
+            Stmt::Try(try_stmt) => try_stmt.try_into(),
+            Stmt::Switch(switch_stmt) => switch_stmt.try_into(),
+            // Only the values are assigned here, the names are hoisted to the enclosing function
+            Stmt::Var(decls) => {
+                let mut block = Block::new(BlockType::Control);
+                block.append(parse_var_decl(VarKind::Var, decls)?);
+                Ok(Box::new(block))
             }
+            other => Err(ParseError::unsupported(describe_stmt(&other))),
         }
     }
 }
 
 /* # Declarations # */
 
-impl From<resast::decl::VarDecl<'_>> for Box<dyn Statement> {
-    fn from(dec: VarDecl) -> Self {
-        let VarDecl { id, mut init } = dec;
-        if let Pat::Ident(id) = id {
-            VariableDeclaration::boxed(
-                &id.name,
-                init.take()
-                    .map(|e| e.into())
-                    .unwrap_or_else(|| Literal::boxed(Value::Undefined) as Box<dyn Expression>),
-            )
-        } else {
-            unimplemented!()
+// A function declaration, which is hoisted to the block it is in rather than converted in place
+impl TryFrom<resast::Func<'_>> for FunctionExpression {
+    type Error = ParseError;
+
+    fn try_from(f: Func<'_>) -> Result<Self, Self::Error> {
+        match function_parts(f, None)? {
+            (Some(name), params, block) => Ok(FunctionExpression::new(Some(name), params, block)),
+            (None, _, _) => Err(ParseError::invalid("function declaration", "missing name")),
         }
     }
 }
 
-impl From<resast::Func<'_>> for Box<dyn Statement> {
-    fn from(f: Func<'_>) -> Self {
-        ExpressionStatement::boxed(f.into())
-    }
-}
+impl TryFrom<resast::decl::Decl<'_>> for Box<dyn Statement> {
+    type Error = ParseError;
 
-impl From<resast::decl::Decl<'_>> for Box<dyn Statement> {
-    fn from(dec: Decl<'_>) -> Self {
+    fn try_from(dec: Decl<'_>) -> Result<Self, Self::Error> {
         match dec {
-            Decl::Func(f) => f.into(),
-            _ => unimplemented!(),
+            Decl::Func(_) => Err(ParseError::unsupported(
+                "function declaration outside a block",
+            )),
+            Decl::Var(_, _) => Err(ParseError::unsupported("variable declaration list")),
+            // A class declaration binds its name like a variable holding the class
+            Decl::Class(class) => {
+                let name = match class.id.as_ref() {
+                    Some(id) => id.name.to_string(),
+                    None => return Err(ParseError::invalid("class declaration", "missing name")),
+                };
+                Ok(VariableDeclaration::boxed(
+                    DeclarationKind::Let,
+                    &name,
+                    Some(class.try_into()?),
+                ))
+            }
+            Decl::Import(_) => Err(ParseError::unsupported("import declaration")),
+            Decl::Export(_) => Err(ParseError::unsupported("export declaration")),
         }
     }
 }
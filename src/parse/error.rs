@@ -0,0 +1,67 @@
+use thiserror::Error;
+
+/// Everything that can go wrong turning source text into IR, either the source is not valid
+/// JavaScript, or it uses a construct the IR cannot represent (yet).
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Error, Clone, Eq, PartialEq)]
+#[error("SyntaxError: {message} ({line}:{column})")]
+pub struct ParseError {
+    /// Line of the offending construct, starting at 1
+    pub line: usize,
+    /// Column of the offending construct
+    pub column: usize,
+    /// The construct that caused the error, either a description like "while statement" or the
+    /// offending source text for plain syntax errors
+    pub construct: String,
+    /// Human readable explanation of the error
+    pub message: String,
+}
+
+impl ParseError {
+    /// A construct that the parser understands, but that has no IR representation.
+    pub fn unsupported(construct: &str) -> Self {
+        Self {
+            line: 0,
+            column: 0,
+            construct: construct.to_owned(),
+            message: format!("{} is not supported", construct),
+        }
+    }
+
+    /// Valid JavaScript that the parser is too old to understand, it reports a syntax error
+    /// instead.
+    pub fn unparseable(construct: &str) -> Self {
+        Self {
+            line: 0,
+            column: 0,
+            construct: construct.to_owned(),
+            message: format!("{} is not supported by the parser", construct),
+        }
+    }
+
+    /// A construct that is supported in general, but not in this particular form or position.
+    pub fn invalid(construct: &str, message: &str) -> Self {
+        Self {
+            line: 0,
+            column: 0,
+            construct: construct.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Name the function or class the construct is nested in, the innermost first. Together
+    /// with the location of the top level statement this narrows down where the error is.
+    pub fn within(mut self, location: &str) -> Self {
+        self.message = format!("{} in {}", self.message, location);
+        self
+    }
+
+    /// Attach a source location to the error. The AST carries no spans, so conversion errors
+    /// are located by the start of the top level statement they occurred in, and name the
+    /// functions and classes between that statement and the construct with `within`.
+    pub fn at(mut self, line: usize, column: usize) -> Self {
+        self.line = line;
+        self.column = column;
+        self
+    }
+}
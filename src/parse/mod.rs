@@ -1,4 +1,6 @@
+pub use error::ParseError;
 pub use parser::parse_program;
 
 mod conversions;
+mod error;
 mod parser;
@@ -1,59 +1,229 @@
+use std::convert::TryInto;
+
 use resast::prelude::*;
 use ressa::Parser;
 
-use crate::ir::{marker::Statement, statement::*};
+use crate::{
+    ir::{expression::Variable, marker::Statement, statement::*},
+    parse::ParseError,
+};
+
+/// Converts a `var`, `let` or `const` declaration list, one statement per declared name.
+pub fn parse_var_decl(
+    kind: VarKind,
+    var_decl: Vec<VarDecl>,
+) -> Result<Vec<Box<dyn Statement>>, ParseError> {
+    let kind = match kind {
+        VarKind::Var => DeclarationKind::Var,
+        VarKind::Let => DeclarationKind::Let,
+        VarKind::Const => DeclarationKind::Const,
+    };
+    var_decl
+        .into_iter()
+        .map(|VarDecl { id, init }| match id {
+            Pat::Ident(id) => {
+                let value = init.map(|e| e.try_into()).transpose()?;
+                let statement: Box<dyn Statement> =
+                    VariableDeclaration::boxed(kind, &id.name, value);
+                Ok(statement)
+            }
+            _ => Err(ParseError::unsupported("destructuring declaration")),
+        })
+        .collect()
+}
+
+/// The names bound by a declaration list, if they are all plain identifiers.
+fn declared_names<'a>(var_decl: &'a [VarDecl]) -> impl Iterator<Item = String> + 'a {
+    var_decl.iter().filter_map(|decl| match &decl.id {
+        Pat::Ident(id) => Some(id.name.to_string()),
+        _ => None,
+    })
+}
+
+/// Every name declared with `var` in `parts`, including those in nested blocks and loops, but not
+/// in nested functions, which have their own. Functions declared in nested blocks are `var`s too,
+/// as Annex B of the spec has it, so that they can be called outside of the block once it ran.
+pub fn var_names(parts: &[ProgramPart]) -> Vec<String> {
+    fn visit_stmt(stmt: &Stmt, names: &mut Vec<String>) {
+        match stmt {
+            Stmt::Var(decls) => names.extend(declared_names(decls)),
+            Stmt::Block(block) => visit_parts(&block.0, names, true),
+            Stmt::If(if_stmt) => {
+                visit_stmt(&if_stmt.consequent, names);
+                if let Some(alternate) = if_stmt.alternate.as_ref() {
+                    visit_stmt(alternate, names);
+                }
+            }
+            Stmt::For(for_stmt) => {
+                if let Some(LoopInit::Variable(VarKind::Var, decls)) = for_stmt.init.as_ref() {
+                    names.extend(declared_names(decls));
+                }
+                visit_stmt(&for_stmt.body, names);
+            }
+            Stmt::ForIn(ForInStmt { left, body, .. })
+            | Stmt::ForOf(ForOfStmt { left, body, .. }) => {
+                if let LoopLeft::Variable(VarKind::Var, decl) = left {
+                    names.extend(declared_names(std::slice::from_ref(decl)));
+                }
+                visit_stmt(body, names);
+            }
+            Stmt::While(WhileStmt { body, .. }) | Stmt::DoWhile(DoWhileStmt { body, .. }) => {
+                visit_stmt(body, names)
+            }
+            Stmt::Labeled(labeled) => visit_stmt(&labeled.body, names),
+            Stmt::Try(try_stmt) => {
+                visit_parts(&try_stmt.block.0, names, true);
+                if let Some(handler) = try_stmt.handler.as_ref() {
+                    visit_parts(&handler.body.0, names, true);
+                }
+                if let Some(finalizer) = try_stmt.finalizer.as_ref() {
+                    visit_parts(&finalizer.0, names, true);
+                }
+            }
+            Stmt::Switch(switch_stmt) => {
+                for case in switch_stmt.cases.iter() {
+                    visit_parts(&case.consequent, names, true);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `nested` is whether the parts are in a block, rather than the body of the function itself
+    fn visit_parts(parts: &[ProgramPart], names: &mut Vec<String>, nested: bool) {
+        for part in parts {
+            match part {
+                ProgramPart::Stmt(stmt) => visit_stmt(stmt, names),
+                ProgramPart::Decl(Decl::Var(VarKind::Var, decls)) => {
+                    names.extend(declared_names(decls))
+                }
+                ProgramPart::Decl(Decl::Func(Func { id: Some(id), .. })) if nested => {
+                    names.push(id.name.to_string())
+                }
+                _ => {}
+            }
+        }
+    }
 
-pub fn parse_var_decl(mut var_decl: Vec<VarDecl>) -> Vec<Box<dyn Statement>> {
-    let mut statements = Vec::new();
-    for sub_dec in var_decl.drain(..) {
-        statements.push(sub_dec.into());
+    let mut names = Vec::new();
+    visit_parts(parts, &mut names, false);
+    let mut unique = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
     }
-    statements
+    unique
 }
 
-pub fn parse_block(statements: Vec<ProgramPart>, block: &mut Block) {
+pub fn parse_block(statements: Vec<ProgramPart>, block: &mut Block) -> Result<(), ParseError> {
     for part in statements {
         match part {
             ProgramPart::Decl(d) => match d {
-                Decl::Var(VarKind::Let, dec) => block.append(parse_var_decl(dec)),
-                Decl::Func(_) => unimplemented!("Nested functions not supported"),
-                _ => unimplemented!("{:?} not allowed in this context", d),
+                Decl::Var(kind, dec) => {
+                    if kind != VarKind::Var {
+                        block.lexical_names.extend(declared_names(&dec));
+                    }
+                    block.append(parse_var_decl(kind, dec)?)
+                }
+                Decl::Func(f) => {
+                    let function: FunctionExpression = f.try_into()?;
+                    // The function already exists by the time its declaration is reached, which
+                    // evaluates to it so that a REPL shows what was declared
+                    let name = function.name().unwrap_or_default();
+                    block.push(ExpressionStatement::boxed(Variable::boxed(name)));
+                    block.functions.push(function);
+                }
+                other => {
+                    if let Decl::Class(Class { id: Some(id), .. }) = &other {
+                        block.lexical_names.push(id.name.to_string());
+                    }
+                    let statement: Box<dyn Statement> = other.try_into()?;
+                    block.push(statement)
+                }
             },
             ProgramPart::Stmt(s) => match s {
-                Stmt::Var(v) => block.append(parse_var_decl(v)),
-                _ => block.push(s.into()),
+                Stmt::Var(v) => block.append(parse_var_decl(VarKind::Var, v)?),
+                _ => block.push(s.try_into()?),
             },
-            _ => unimplemented!(),
+            ProgramPart::Dir(_) => return Err(ParseError::unsupported("directive")),
+        }
+    }
+    Ok(())
+}
+
+/// Converts one top level program part into IR, appending it to `ir`.
+fn parse_program_part(part: ProgramPart, ir: &mut Block) -> Result<(), ParseError> {
+    for name in var_names(std::slice::from_ref(&part)) {
+        if !ir.var_names.contains(&name) {
+            ir.var_names.push(name);
         }
     }
+    parse_block(vec![part], ir)
 }
 
-pub fn parse_program(input: &str) -> Block {
-    // parse
-    let mut parser = Parser::new(input).unwrap();
-    let ast = parser.parse().expect("Failed to parse");
+/// The source text at `line`:`column`, up to the next whitespace, used to name the offending
+/// token in syntax errors.
+fn token_at(input: &str, line: usize, column: usize) -> String {
+    input
+        .lines()
+        .nth(line.saturating_sub(1))
+        .and_then(|l| l.get(column.saturating_sub(1)..))
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or("end of input")
+        .to_owned()
+}
+
+// Operators that are newer than the parser, which fails on them with an unexpected token. Longer
+// ones come first, so `??=` isn't taken for `??`
+const UNPARSEABLE_OPERATORS: [&str; 4] = ["??=", "&&=", "||=", "??"];
+
+/// The operator the parser doesn't know that a syntax error at `line`:`column` stopped at, if
+/// any. The parser stops somewhere inside of it, e.g. at the second `?` of `??`.
+fn unparseable_operator(input: &str, line: usize, column: usize) -> Option<&'static str> {
+    let line = input.lines().nth(line.saturating_sub(1))?;
+    let at = column.saturating_sub(1);
+    UNPARSEABLE_OPERATORS.into_iter().find(|operator| {
+        (at.saturating_sub(operator.len() - 1)..=at)
+            .any(|start| line.get(start..start + operator.len()) == Some(*operator))
+    })
+}
+
+/// Parses `input` into the IR, reporting the location of the first syntax error or unsupported
+/// construct instead of panicking.
+pub fn parse_program(input: &str) -> Result<Block, ParseError> {
+    let syntax_error = |e: ressa::Error, line: usize, column: usize| {
+        match unparseable_operator(input, line, column) {
+            Some(operator) => ParseError::unparseable(&format!("'{}' operator", operator)),
+            None => ParseError::invalid(&token_at(input, line, column), &e.to_string()),
+        }
+        .at(line, column)
+    };
+
+    let mut parser = Parser::new(input).map_err(|e| syntax_error(e, 1, 1))?;
 
     // programmatically construct IR from AST
     let mut ir = Block::new(BlockType::Global);
 
-    if let Program::Script(ast_nodes) = ast {
-        for node in ast_nodes {
-            match node {
-                ProgramPart::Decl(dec) => match dec {
-                    Decl::Var(VarKind::Let, mut dec) => {
-                        for sub_dec in dec.drain(..) {
-                            ir.push(sub_dec.into());
-                        }
-                    }
-                    Decl::Func(f) => {
-                        ir.push(f.into());
-                    }
-                    _ => panic!("Unsupported Declaration"),
-                },
-                ProgramPart::Stmt(s) => ir.push(s.into()),
-                ProgramPart::Dir(_) => panic!("Directives not supported"),
+    // Go part by part so that errors can be located at the statement they occurred in
+    loop {
+        let start = parser.next_position().start;
+        let part = match parser.next() {
+            None => break,
+            Some(Ok(part)) => part,
+            Some(Err(e)) => {
+                let at = parser.next_position().start;
+                return Err(syntax_error(e, at.line, at.column));
             }
-        }
+        };
+        parse_program_part(part, &mut ir).map_err(|e| e.at(start.line, start.column))?;
     }
-    ir
+
+    // Functions declared at the top level are properties of the global object, a `var` of the
+    // same name would hide them
+    let functions = &ir.functions;
+    ir.var_names
+        .retain(|name| !functions.iter().any(|f| f.name() == Some(name.as_str())));
+
+    Ok(ir)
 }
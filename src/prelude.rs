@@ -1,4 +1,4 @@
 pub use crate::ir::IrNode;
-pub use crate::parse::parse_program;
+pub use crate::parse::{parse_program, ParseError};
 pub use crate::runtime::*;
 pub use crate::util::get_input;
@@ -0,0 +1,183 @@
+use std::io::{self, BufRead, Write};
+
+use thiserror::Error;
+
+use crate::{
+    parse::{parse_program, ParseError},
+    runtime::{Exception, Interpreter, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Error, Clone, PartialEq)]
+pub enum ReplError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("Uncaught {0}")]
+    Exception(#[from] Exception),
+}
+
+/// A read-eval-print loop around a single interpreter, so that everything declared at the top
+/// level of one entry is visible to every entry after it.
+#[derive(Default)]
+pub struct Repl {
+    interpreter: Interpreter,
+    // Lines of an entry that isn't finished yet, e.g. a function body with an open brace
+    buffer: String,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads entries from stdin until EOF, printing the completion value of each.
+    pub fn run(&mut self) {
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+
+        loop {
+            let prompt = if self.buffer.is_empty() { "> " } else { "... " };
+            print!("{}", prompt);
+            io::stdout().flush().ok();
+
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                // EOF or an unreadable stdin both end the session
+                _ => break,
+            };
+
+            if let Some(output) = self.feed(&line).as_ref().map(echo) {
+                println!("{}", output);
+            }
+        }
+        println!();
+    }
+
+    /// Adds a line of input, evaluating the buffered entry if it is complete. Returns [`None`]
+    /// if more input is needed.
+    pub fn feed(&mut self, line: &str) -> Option<Result<Value, ReplError>> {
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+
+        if is_incomplete(&self.buffer) {
+            return None;
+        }
+
+        let entry = std::mem::take(&mut self.buffer);
+        if entry.trim().is_empty() {
+            return None;
+        }
+
+        Some(self.eval(&entry))
+    }
+
+    /// Evaluates a complete entry against the persistent interpreter state.
+    pub fn eval(&mut self, entry: &str) -> Result<Value, ReplError> {
+        let program = parse_program(entry)?;
+        // Uncaught exceptions are echoed like any other outcome, not reported by the interpreter
+        Ok(self.interpreter.run_quietly(program)?)
+    }
+}
+
+/// What the REPL prints for the outcome of an entry. Objects that refer back to
+/// themselves are printed with "[Circular]" in place of the reference.
+pub fn echo(result: &Result<Value, ReplError>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Whether `input` has more opening brackets than closing ones, ignoring any inside of strings
+/// and comments, meaning the user is still typing it.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut depth = 0i64;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            '"' | '\'' | '`' => {
+                // Skip to the matching quote, minding escapes
+                while let Some(s) = chars.next() {
+                    match s {
+                        '\\' => {
+                            chars.next();
+                        }
+                        s if s == c => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' => match chars.peek() {
+                Some('/') => {
+                    chars.by_ref().find(|&s| s == '\n');
+                }
+                Some('*') => {
+                    chars.next();
+                    let mut previous = ' ';
+                    for s in chars.by_ref() {
+                        if previous == '*' && s == '/' {
+                            break;
+                        }
+                        previous = s;
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    depth > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_input_is_complete() {
+        assert!(!is_incomplete("let x = {a: [1, 2]}"));
+        assert!(!is_incomplete("function f() { return 1 }"));
+    }
+
+    #[test]
+    fn open_brackets_are_incomplete() {
+        assert!(is_incomplete("function f() {"));
+        assert!(is_incomplete("let x = [1,"));
+    }
+
+    #[test]
+    fn brackets_in_strings_and_comments_are_ignored() {
+        assert!(!is_incomplete("let s = \"{\" // {"));
+        assert!(!is_incomplete("let s = '\\'{' /* ( */"));
+        assert!(is_incomplete("if (x) { // }"));
+    }
+
+    #[test]
+    fn cyclic_values_are_echoed() {
+        let mut repl = Repl::new();
+        repl.feed("var o = {}; o.self = o; var a = [o]; a.push(a)");
+        let object = repl.feed("o");
+        assert_eq!(echo(object.as_ref().unwrap()), "{ self: [Circular], }");
+        let array = repl.feed("a");
+        assert_eq!(
+            echo(array.as_ref().unwrap()),
+            "[{ self: [Circular], }, [Circular]]"
+        );
+    }
+
+    #[test]
+    fn uncaught_exceptions_are_echoed() {
+        let mut repl = Repl::new();
+        let thrown = repl.feed("throw 5");
+        assert_eq!(echo(thrown.as_ref().unwrap()), "Uncaught 5");
+        let missing = repl.feed("missing");
+        assert_eq!(
+            echo(missing.as_ref().unwrap()),
+            "Uncaught Cannot find variable \"missing\""
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fmt, rc::Rc};
+
+use crate::runtime::{Handle, Keys, Object, ObjectType, Properties, Prototype, Trace, Value};
+
+/// A property defined by a getter and a setter, like `get x() {}` in a class. It is stored in
+/// place of the property's value, and reading or assigning the property calls the functions
+/// instead, so it is never seen by scripts.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Object, Clone, Default)]
+#[object_type(Accessor)]
+pub struct Accessor {
+    properties: Properties,
+    pub getter: Option<Handle>,
+    pub setter: Option<Handle>,
+}
+
+impl Accessor {
+    pub fn boxed() -> Box<Self> {
+        Box::default()
+    }
+
+    /// The getter and setter, if `value` is an accessor rather than the value of a property.
+    pub fn functions(value: &Value) -> Option<(Option<Handle>, Option<Handle>)> {
+        match value {
+            Value::Object(o) if o.borrow().get_type() == ObjectType::Accessor => {
+                let mut accessor = o.borrow_mut();
+                let accessor = accessor.as_accessor();
+                Some((accessor.getter.clone(), accessor.setter.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Accessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.getter.is_some(), self.setter.is_some()) {
+            (true, true) => f.write_str("[Getter/Setter]"),
+            (true, false) => f.write_str("[Getter]"),
+            _ => f.write_str("[Setter]"),
+        }
+    }
+}
+
+impl Keys for Accessor {
+    fn own_keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// Accessors are only ever looked through, never inherited from
+impl Prototype for Accessor {
+    fn prototype(&self) -> Option<Handle> {
+        None
+    }
+
+    fn set_prototype(&mut self, _prototype: Option<Handle>) {}
+}
+
+impl Trace for Accessor {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        for function in self.getter.iter().chain(self.setter.iter()) {
+            visit(&Value::Object(Rc::clone(function)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.getter = None;
+        self.setter = None;
+    }
+}
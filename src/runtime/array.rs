@@ -0,0 +1,512 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+
+use crate::runtime::{
+    conversions, exception::*, object, Handle, Interpreter, Keys, Object, ObjectType, Properties,
+    Prototype, Trace, Value,
+};
+
+/// The methods of `Array.prototype`, each one is a built-in function named `array_<method>`.
+pub const METHODS: [&str; 12] = [
+    "push", "pop", "shift", "unshift", "slice", "splice", "indexOf", "join", "concat", "reverse",
+    "values", "toString",
+];
+
+/// The longest an array can be, its indices are all below this.
+const MAX_LENGTH: usize = u32::MAX as usize;
+
+/// How far past the last element one can be written and still be stored with the others, the
+/// ones in between become undefined. Elements written further out are stored apart, so that
+/// `a[1e9] = 1` doesn't allocate a billion of them.
+const DENSE_GAP: usize = 1024;
+
+/// The longest an array can be for the methods that work on all of its elements at once, which
+/// need them stored together.
+const MAX_DENSE_LENGTH: usize = 1 << 22;
+
+/// An array, its elements are addressed by the properties "0", "1", and so on, with `length`
+/// always one past the last of them. They are stored densely, except for the ones written far
+/// past the others.
+///
+/// Elements that were never written inside the dense part are undefined rather than holes, and so
+/// are deleted ones: `delete a[1]` leaves `1 in a` true.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub struct ArrayObject {
+    properties: Properties,
+    elements: Vec<Value>,
+    // The elements too far past the others to be stored with them, all of them are past the end
+    // of `elements`
+    sparse: BTreeMap<usize, Value>,
+    // A value, so that it can be handed out by reference
+    length: Value,
+    // Where the methods come from
+    prototype: Option<Handle>,
+    // The keys of the properties that aren't enumerated
+    hidden: HashSet<String>,
+}
+
+impl ArrayObject {
+    pub fn new(elements: Vec<Value>, prototype: Option<Handle>) -> Self {
+        let length = Value::Number(elements.len() as f64);
+        Self {
+            properties: Properties::new(),
+            elements,
+            sparse: BTreeMap::new(),
+            length,
+            prototype,
+            hidden: HashSet::new(),
+        }
+    }
+
+    pub fn boxed(elements: Vec<Value>, prototype: Option<Handle>) -> Box<Self> {
+        Box::new(Self::new(elements, prototype))
+    }
+
+    /// The element at `index`, undefined for one that was never written, or nothing past the
+    /// end.
+    pub fn element(&self, index: usize) -> Option<Value> {
+        if index >= self.len() {
+            return None;
+        }
+        let element = self.elements.get(index).or_else(|| self.sparse.get(&index));
+        Some(element.cloned().unwrap_or_default())
+    }
+
+    fn len(&self) -> usize {
+        match self.length {
+            Value::Number(n) => n as usize,
+            _ => unreachable!("The length of an array is always a number"),
+        }
+    }
+
+    fn sync_length(&mut self) {
+        self.length = Value::Number(self.elements.len() as f64);
+    }
+
+    fn set_length(&mut self, value: Value) -> Result<(), Exception> {
+        match value {
+            Value::Number(n) if n >= 0f64 && n.fract() == 0f64 && n <= MAX_LENGTH as f64 => {
+                self.truncate(n as usize);
+                self.length = value;
+                Ok(())
+            }
+            _ => Err(Exception::RangeError("Invalid array length".to_owned())),
+        }
+    }
+
+    /// Drops the elements at `length` and past it, without changing the length.
+    fn truncate(&mut self, length: usize) {
+        self.elements.truncate(length);
+        self.sparse.split_off(&length);
+    }
+
+    fn set_element(&mut self, index: usize, value: Value) {
+        if index < self.elements.len() {
+            self.elements[index] = value;
+        } else if index > self.elements.len() + DENSE_GAP {
+            self.sparse.insert(index, value);
+        } else {
+            self.elements.resize(index, Value::Undefined);
+            self.elements.push(value);
+            // The elements stored apart may be close enough to the others now
+            let further = self.sparse.split_off(&self.elements.len());
+            for (index, element) in std::mem::replace(&mut self.sparse, further) {
+                self.elements[index] = element;
+            }
+            while let Some(element) = self.sparse.remove(&self.elements.len()) {
+                self.elements.push(element);
+            }
+        }
+        if index >= self.len() {
+            self.length = Value::Number((index + 1) as f64);
+        }
+    }
+
+    /// Stores every element with the others, so that a method can work on all of them at once.
+    fn make_dense(&mut self, method: &str) -> Result<(), Exception> {
+        let length = self.len();
+        if length > self.elements.len() {
+            if length > MAX_DENSE_LENGTH {
+                bail!(RangeError(format!(
+                    "Array is too long for Array.prototype.{}",
+                    method
+                )));
+            }
+            self.elements.resize(length, Value::Undefined);
+            for (index, element) in std::mem::take(&mut self.sparse) {
+                self.elements[index] = element;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The index a property name refers to, if it is written the way an index would be. Larger
+/// numbers than an index can be are ordinary property names.
+fn index(name: &str) -> Option<usize> {
+    name.parse::<usize>()
+        .ok()
+        .filter(|index| *index < MAX_LENGTH && index.to_string() == name)
+}
+
+impl Object for ArrayObject {
+    fn put(&mut self, name: &str, value: Value) {
+        if let Some(index) = index(name) {
+            self.set_element(index, value);
+        } else if name == "length" {
+            // Nothing to report an invalid length to, so it is ignored
+            self.set_length(value).ok();
+        } else {
+            self.properties.insert(name.to_owned(), value);
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        match index(name) {
+            Some(index) => self.elements.get(index).or_else(|| self.sparse.get(&index)),
+            None if name == "length" => Some(&self.length),
+            None => self.properties.get(name),
+        }
+    }
+
+    // The length isn't handed out mutably, it has to go through `set` to resize the elements
+    fn get_mut(&mut self, name: &str) -> Option<&mut Value> {
+        match index(name) {
+            Some(index) => match self.elements.get_mut(index) {
+                Some(element) => Some(element),
+                None => self.sparse.get_mut(&index),
+            },
+            None if name == "length" => None,
+            None => self.properties.get_mut(name),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Value> {
+        match self.get(name) {
+            Some(value) => Some(value.clone()),
+            // Reading past the end is how a program finds out it went too far, it isn't an error
+            None if index(name).is_some() => Some(Value::Undefined),
+            None => self
+                .prototype
+                .as_ref()
+                .and_then(|prototype| prototype.borrow().lookup(name)),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: Value) -> Result<(), Exception> {
+        if name == "length" {
+            return self.set_length(value);
+        }
+        self.put(name, value);
+        Ok(())
+    }
+
+    fn get_type(&self) -> ObjectType {
+        ObjectType::Array
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_object(self: Box<Self>) -> Box<dyn Object> {
+        self
+    }
+
+    fn format_properties(&self) -> String {
+        let mut s = String::from("{ ");
+        for (i, element) in self.elements.iter().enumerate() {
+            s += &format!("{}: {}, ", i, element);
+        }
+        for (i, element) in self.sparse.iter() {
+            s += &format!("{}: {}, ", i, element);
+        }
+        for (k, v) in self.properties.iter() {
+            s += &format!("{}: {}, ", k, v);
+        }
+        s += "}";
+        s
+    }
+}
+
+impl Keys for ArrayObject {
+    fn own_keys(&self) -> Vec<String> {
+        let indices = (0..self.elements.len()).chain(self.sparse.keys().copied());
+        indices
+            .map(|index| index.to_string())
+            .chain(object::enumerable_keys(&self.properties, &self.hidden))
+            .collect()
+    }
+
+    fn hide(&mut self, key: &str) {
+        self.hidden.insert(key.to_owned());
+    }
+
+    // There are no holes, a deleted element becomes undefined and the length stays
+    fn delete(&mut self, key: &str) -> bool {
+        match index(key) {
+            Some(index) => {
+                if let Some(element) = self.elements.get_mut(index) {
+                    *element = Value::Undefined;
+                }
+                self.sparse.remove(&index);
+                true
+            }
+            None if key == "length" => false,
+            None => {
+                self.properties.remove(key);
+                self.hidden.remove(key);
+                true
+            }
+        }
+    }
+}
+
+impl Prototype for ArrayObject {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for ArrayObject {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.elements.iter().for_each(&mut *visit);
+        self.sparse.values().for_each(&mut *visit);
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.elements.clear();
+        self.sparse.clear();
+        self.sync_length();
+        self.properties.clear();
+        self.prototype = None;
+    }
+}
+
+impl fmt::Display for ArrayObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut elements: Vec<String> = self.elements.iter().map(Value::to_string).collect();
+        // The elements never written in between those stored apart, and up to the length
+        let mut next = self.elements.len();
+        let sparse = self
+            .sparse
+            .iter()
+            .map(|(index, element)| (*index, Some(element)));
+        for (index, element) in sparse.chain(std::iter::once((self.len(), None))) {
+            if index > next {
+                elements.push(format!("<{} empty items>", index - next));
+            }
+            if let Some(element) = element {
+                elements.push(element.to_string());
+            }
+            next = index + 1;
+        }
+        write!(f, "[{}]", elements.join(", "))
+    }
+}
+
+/// Converts the value assigned to the property `key` of `object` the way the object stores it. The
+/// `length` of an array is converted to a number first, which may call back into the script.
+pub fn assigned_value(
+    interpreter: &mut Interpreter,
+    object: &Handle,
+    key: &str,
+    value: Value,
+) -> Result<Value, Exception> {
+    if key == "length" && object.borrow().get_type() == ObjectType::Array {
+        return conversions::to_number(interpreter, value).map(Value::Number);
+    }
+    Ok(value)
+}
+
+// The argument at `position` converted to an integer, with NaN as 0, the way index and count
+// arguments are. Nothing if it is missing or undefined
+fn integer_argument(
+    interpreter: &mut Interpreter,
+    arguments: &[Value],
+    position: usize,
+) -> Result<Option<f64>, Exception> {
+    match arguments.get(position) {
+        None | Some(Value::Undefined) => Ok(None),
+        Some(argument) => {
+            let n = conversions::to_number(interpreter, argument.clone())?;
+            Ok(Some(if n.is_nan() { 0f64 } else { n.trunc() }))
+        }
+    }
+}
+
+/// Resolves an index argument of `slice`, `splice` and `indexOf`, which count from the end when
+/// negative.
+fn relative_index(argument: Option<f64>, length: usize, default: usize) -> usize {
+    match argument {
+        Some(n) if n < 0f64 => (length as f64 + n).max(0f64) as usize,
+        Some(n) => n.min(length as f64) as usize,
+        None => default,
+    }
+}
+
+/// Runs the method of `Array.prototype` called `method` on `this`.
+pub fn call_method(
+    interpreter: &mut Interpreter,
+    method: &str,
+    this: Value,
+    arguments: Vec<Value>,
+) -> Result<Value, Exception> {
+    let handle = match &this {
+        Value::Object(o) if o.borrow().get_type() == ObjectType::Array => Rc::clone(o),
+        _ => bail!(TypeError(format!(
+            "Array.prototype.{} called on a value that isn't an array",
+            method
+        ))),
+    };
+
+    // The length is read before the arguments are converted. Converting them may call back into
+    // the script, which may use this array, so it is done before the array is borrowed
+    let length = handle.borrow_mut().as_array().len();
+    let (first, second) = match method {
+        "slice" | "splice" => (
+            integer_argument(interpreter, &arguments, 0)?,
+            integer_argument(interpreter, &arguments, 1)?,
+        ),
+        "indexOf" => (None, integer_argument(interpreter, &arguments, 1)?),
+        _ => (None, None),
+    };
+    let separator = match arguments.first() {
+        Some(separator) if method == "join" && *separator != Value::Undefined => {
+            conversions::to_string(interpreter, separator.clone())?
+        }
+        _ => String::from(","),
+    };
+
+    let mut array_borrow = handle.borrow_mut();
+    let array = array_borrow.as_array();
+    // Elements the conversions removed are gone, those they added aren't visited
+    let length = length.min(array.len());
+    if method != "push" && method != "pop" {
+        array.make_dense(method)?;
+    }
+
+    let value = match method {
+        "push" => {
+            if length + arguments.len() > MAX_LENGTH {
+                bail!(RangeError("Invalid array length".to_owned()));
+            }
+            for (i, argument) in arguments.into_iter().enumerate() {
+                array.set_element(length + i, argument);
+            }
+            array.length.clone()
+        }
+        "pop" => match length.checked_sub(1) {
+            Some(last) => {
+                let element = array.element(last).unwrap_or_default();
+                array.truncate(last);
+                array.length = Value::Number(last as f64);
+                element
+            }
+            None => Value::Undefined,
+        },
+        "shift" => {
+            let first = (length > 0).then(|| array.elements.remove(0));
+            array.sync_length();
+            first.unwrap_or_default()
+        }
+        "unshift" => {
+            array.elements.splice(0..0, arguments);
+            array.sync_length();
+            array.length.clone()
+        }
+        "slice" => {
+            let start = relative_index(first, length, 0);
+            let end = relative_index(second, length, length).max(start);
+            let elements = array.elements[start..end].to_vec();
+            drop(array_borrow);
+            interpreter.new_array(elements)
+        }
+        "splice" => {
+            let start = relative_index(first, length, 0);
+            // Without a start nothing is deleted, without a count everything after the start is
+            let delete_count = match arguments.len() {
+                0 => 0,
+                1 => length - start,
+                _ => second.unwrap_or(0f64).clamp(0f64, (length - start) as f64) as usize,
+            };
+            let items = arguments.into_iter().skip(2);
+            let removed = array
+                .elements
+                .splice(start..start + delete_count, items)
+                .collect();
+            array.sync_length();
+            drop(array_borrow);
+            interpreter.new_array(removed)
+        }
+        "indexOf" => {
+            let search = arguments.first().cloned().unwrap_or_default();
+            let from = relative_index(second, length, 0);
+            let position = array.elements[from..]
+                .iter()
+                .position(|element| *element == search);
+            Value::Number(position.map_or(-1f64, |i| (from + i) as f64))
+        }
+        "join" | "toString" => {
+            // Converting an element may call back into the script, which may use this array
+            let elements = array.elements.clone();
+            drop(array_borrow);
+            let joined = interpreter.join_array(&handle, |interpreter| {
+                let elements = elements
+                    .into_iter()
+                    .map(|element| match element {
+                        Value::Undefined | Value::Null => Ok(String::new()),
+                        other => conversions::to_string(interpreter, other),
+                    })
+                    .collect::<Result<Vec<_>, Exception>>()?;
+                Ok(elements.join(&separator))
+            })?;
+            Value::StringLiteral(joined)
+        }
+        "concat" => {
+            let mut elements = array.elements.clone();
+            // Done with this array, it may be one of the arguments
+            drop(array_borrow);
+            for argument in arguments {
+                match argument {
+                    Value::Object(o) if o.borrow().get_type() == ObjectType::Array => {
+                        let mut argument = o.borrow_mut();
+                        let argument = argument.as_array();
+                        argument.make_dense(method)?;
+                        elements.extend_from_slice(&argument.elements);
+                    }
+                    other => elements.push(other),
+                }
+            }
+            interpreter.new_array(elements)
+        }
+        "reverse" => {
+            array.elements.reverse();
+            drop(array_borrow);
+            this
+        }
+        "values" => {
+            drop(array_borrow);
+            interpreter.new_array_iterator(this)
+        }
+        _ => bail!(TypeError(format!(
+            "Array.prototype.{} is not a function",
+            method
+        ))),
+    };
+
+    Ok(value)
+}
@@ -0,0 +1,593 @@
+//! The instruction set run by the [`Vm`](crate::runtime::Vm), and the [`Compiler`] that lowers
+//! the IR into it through [`IrNode::emit_bytecode`].
+//!
+//! Every node compiles to code that leaves exactly one value on the stack, its completion value,
+//! which is what lets a block hand back the value of its last statement like the tree-walker does.
+use std::{fmt, rc::Rc};
+
+use thiserror::Error;
+
+use crate::{
+    ir::{
+        ops::{BinaryOperator, LogicalOperator, UnaryOperator},
+        statement::Block,
+        IrNode,
+    },
+    runtime::{class::ClassDefinition, Exception, Value},
+};
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Error, Clone, Eq, PartialEq)]
+pub enum CompileError {
+    #[error("{0} cannot be compiled to bytecode")]
+    Unsupported(String),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+impl From<CompileError> for Exception {
+    fn from(e: CompileError) -> Self {
+        Exception::Exception(e.to_string())
+    }
+}
+
+/// Operands that refer to names, constants, functions or object shapes are indices into the
+/// corresponding table of the [`Chunk`] the instruction belongs to.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub enum Instruction {
+    /// Push a constant
+    Constant(usize),
+    /// Push undefined
+    Undefined,
+    /// Discard the top of the stack
+    Pop,
+    /// Duplicate the top of the stack
+    Dup,
+    /// Duplicate the top two values of the stack, keeping their order
+    Dup2,
+    /// Pop a value, and put it back below the given number of values
+    Bury(usize),
+    /// Push the value of a variable, using scope resolution
+    GetVariable(usize),
+    /// Same as [`Instruction::GetVariable`], pushing undefined for a variable that isn't
+    /// declared, which is what `typeof` needs
+    GetVariableIfDeclared(usize),
+    /// Assign the top of the stack to an existing variable, leaving it on the stack
+    SetVariable(usize),
+    /// Pop the top of the stack into a new variable in the innermost scope
+    DeclareVariable(usize),
+    /// Pop the top of the stack into a new constant in the innermost scope
+    DeclareConstant(usize),
+    /// Bind a `var` to undefined in the innermost scope, unless it is bound there already
+    HoistVar(usize),
+    /// Assign the value on top of the stack to the binding of a name, if it is a `var`, leaving
+    /// the value
+    SetVar(usize),
+    /// Bind a `let`, `const` or class in the innermost scope, which can't be used until a
+    /// declaration initializes it
+    HoistLexical(usize),
+    /// Assign the top of the stack to a property of the global object, leaving it on the stack
+    SetGlobal(usize),
+    /// Declare a top level `var` as a property of the global object, unless it is one already
+    HoistGlobal(usize),
+    /// Pop an object and push one of its properties
+    GetProperty(usize),
+    /// Pop a key and an object, and push the property of the object the key selects
+    GetIndex,
+    /// Push a property of the object on top of the stack, which is left there as the receiver
+    /// of a following [`Instruction::CallMethod`]
+    GetMethod(usize),
+    /// Pop an object and assign the value below it to one of its properties, leaving the value
+    SetProperty(usize),
+    /// Pop a key and an object, and assign the value below them to the property the key selects,
+    /// leaving the value
+    SetIndex,
+    /// Pop an object, delete one of its properties and push whether it is gone
+    DeleteProperty(usize),
+    /// Pop a key and an object, delete the property the key selects and push whether it is gone
+    DeleteIndex,
+    /// Pop the receiver and push a property of what the home object of the running method
+    /// inherits from, getters are called on the receiver
+    GetSuper(usize),
+    /// Push the constructor the class of the running constructor extends
+    SuperConstructor,
+    /// Pop an operand and push the result of the operator
+    Unary(UnaryOperator),
+    /// Pop two operands and push the result of the operator
+    Binary(BinaryOperator),
+    /// Continue at the given instruction
+    Jump(usize),
+    /// Pop a value, and continue at the given instruction if it is falsy
+    JumpIfFalse(usize),
+    /// Continue at the given instruction, keeping the value on top of the stack as the result, if
+    /// the operator short-circuits on it. Pop it otherwise
+    ShortCircuit(LogicalOperator, usize),
+    /// Push a new innermost scope
+    EnterScope,
+    /// Pop the innermost scope
+    LeaveScope,
+    /// Replace the innermost scope with a copy of its bindings
+    CopyScope,
+    /// Push a new function object
+    MakeFunction(usize),
+    /// Pop one value per key of the shape, and push an object made out of them
+    MakeObject(usize),
+    /// Pop the given number of values, and push an array of them
+    MakeArray(usize),
+    /// Push the constructor of a new class, popping the class it extends first if it has one
+    MakeClass(usize),
+    /// Pop the arguments and then the callee, and call it. The operand names the callee, the way
+    /// it was written
+    Call(usize, usize),
+    /// Pop the given number of arguments and a constructor, and call it with `this` bound to a
+    /// new object inheriting from its `prototype`, which is the result unless it returns an object
+    Construct(usize),
+    /// Same as [`Instruction::Call`], also popping the receiver from below the callee
+    CallMethod(usize, usize),
+    /// Pop the given number of arguments, the constructor of the parent class and the value for
+    /// `this` below it, and call the constructor, which may be a class constructor
+    CallSuper(usize),
+    /// Pop the return value, leave the current frame and push it for the caller
+    Return,
+    /// Pop a value and throw it
+    Throw,
+    /// Catch exceptions thrown until the matching [`Instruction::PopHandler`]: the stack and
+    /// scope are restored to what they are now, the caught value is pushed and execution continues
+    /// at the given instruction
+    PushHandler(usize),
+    /// Stop catching exceptions with the handler pushed last
+    PopHandler,
+    /// Pop a value and push an iterator over the keys a for-in loop visits
+    EnumerateKeys,
+    /// Pop an iterable, and push it as the receiver of its iterator method, followed by the method
+    GetIterator,
+    /// Pop the result of an iterator's `next`, and continue at the given instruction if it is
+    /// done, pushing its value otherwise
+    IteratorValue(usize),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Instruction::*;
+        match self {
+            Constant(i) => write!(f, "Constant {}", i),
+            Undefined => f.write_str("Undefined"),
+            Pop => f.write_str("Pop"),
+            Dup => f.write_str("Dup"),
+            Dup2 => f.write_str("Dup2"),
+            Bury(depth) => write!(f, "Bury {}", depth),
+            GetVariable(i) => write!(f, "GetVariable {}", i),
+            GetVariableIfDeclared(i) => write!(f, "GetVariableIfDeclared {}", i),
+            SetVariable(i) => write!(f, "SetVariable {}", i),
+            DeclareVariable(i) => write!(f, "DeclareVariable {}", i),
+            DeclareConstant(i) => write!(f, "DeclareConstant {}", i),
+            HoistVar(i) => write!(f, "HoistVar {}", i),
+            SetVar(i) => write!(f, "SetVar {}", i),
+            HoistLexical(i) => write!(f, "HoistLexical {}", i),
+            SetGlobal(i) => write!(f, "SetGlobal {}", i),
+            HoistGlobal(i) => write!(f, "HoistGlobal {}", i),
+            GetProperty(i) => write!(f, "GetProperty {}", i),
+            GetIndex => f.write_str("GetIndex"),
+            DeleteProperty(i) => write!(f, "DeleteProperty {}", i),
+            DeleteIndex => f.write_str("DeleteIndex"),
+            GetMethod(i) => write!(f, "GetMethod {}", i),
+            SetProperty(i) => write!(f, "SetProperty {}", i),
+            SetIndex => f.write_str("SetIndex"),
+            GetSuper(i) => write!(f, "GetSuper {}", i),
+            SuperConstructor => f.write_str("SuperConstructor"),
+            Unary(op) => write!(f, "Unary {}", op),
+            Binary(op) => write!(f, "Binary {}", op),
+            Jump(to) => write!(f, "Jump {}", to),
+            JumpIfFalse(to) => write!(f, "JumpIfFalse {}", to),
+            ShortCircuit(op, to) => write!(f, "ShortCircuit {} {}", op, to),
+            EnterScope => f.write_str("EnterScope"),
+            LeaveScope => f.write_str("LeaveScope"),
+            CopyScope => f.write_str("CopyScope"),
+            MakeFunction(i) => write!(f, "MakeFunction {}", i),
+            MakeObject(i) => write!(f, "MakeObject {}", i),
+            MakeArray(length) => write!(f, "MakeArray {}", length),
+            MakeClass(i) => write!(f, "MakeClass {}", i),
+            Call(argc, callee) => write!(f, "Call {} {}", argc, callee),
+            Construct(argc) => write!(f, "Construct {}", argc),
+            CallMethod(argc, callee) => write!(f, "CallMethod {} {}", argc, callee),
+            CallSuper(argc) => write!(f, "CallSuper {}", argc),
+            Return => f.write_str("Return"),
+            Throw => f.write_str("Throw"),
+            PushHandler(to) => write!(f, "PushHandler {}", to),
+            PopHandler => f.write_str("PopHandler"),
+            EnumerateKeys => f.write_str("EnumerateKeys"),
+            GetIterator => f.write_str("GetIterator"),
+            IteratorValue(to) => write!(f, "IteratorValue {}", to),
+        }
+    }
+}
+
+/// A function expression compiled ahead of time, from which [`Instruction::MakeFunction`]
+/// creates function objects.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub struct FunctionTemplate {
+    pub name: Option<String>,
+    pub parameters: Vec<String>,
+    // Kept so the function objects stay callable from the tree-walker
+    pub body: Block,
+    pub chunk: Rc<Chunk>,
+    pub is_arrow: bool,
+}
+
+/// A unit of compiled code, either a whole program or a function body.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default)]
+pub struct Chunk {
+    pub code: Vec<Instruction>,
+    pub constants: Vec<Value>,
+    pub names: Vec<String>,
+    pub functions: Vec<Rc<FunctionTemplate>>,
+    pub shapes: Vec<Vec<String>>,
+    pub classes: Vec<Rc<ClassDefinition>>,
+}
+
+impl Chunk {
+    /// A listing of the instructions, with the operands that index tables resolved.
+    pub fn disassemble(&self) -> String {
+        let mut output = String::new();
+        for (index, instruction) in self.code.iter().enumerate() {
+            output += &format!("{:>4} {}", index, instruction);
+            match instruction {
+                Instruction::Constant(i) => output += &format!(" ({})", self.constants[*i]),
+                Instruction::GetVariable(i)
+                | Instruction::SetVariable(i)
+                | Instruction::DeclareVariable(i)
+                | Instruction::SetVar(i)
+                | Instruction::SetGlobal(i)
+                | Instruction::HoistGlobal(i)
+                | Instruction::GetVariableIfDeclared(i)
+                | Instruction::GetProperty(i)
+                | Instruction::DeleteProperty(i)
+                | Instruction::GetMethod(i)
+                | Instruction::SetProperty(i)
+                | Instruction::GetSuper(i)
+                | Instruction::Call(_, i)
+                | Instruction::CallMethod(_, i) => output += &format!(" ({})", self.names[*i]),
+                Instruction::MakeObject(i) => output += &format!(" {:?}", self.shapes[*i]),
+                Instruction::MakeClass(i) => {
+                    let name = self.classes[*i].name.as_deref().unwrap_or("Anonymous");
+                    output += &format!(" ({})", name)
+                }
+                _ => (),
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+// The statements a break, continue or return can jump out of
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ContextKind {
+    Loop,
+    Switch,
+    Labeled,
+    // The part of a try statement that runs with a handler pushed, no jump targets it
+    Try,
+}
+
+// Where a loop, switch, labeled statement or try block started, so a break, continue or return
+// knows what to unwind and which jumps to patch
+struct JumpContext {
+    labels: Vec<String>,
+    kind: ContextKind,
+    scope_depth: usize,
+    held_values: usize,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+    // The finally block a jump out of a try block runs first
+    finalizer: Option<Block>,
+}
+
+/// Walks the IR, collecting the instructions each node emits into a [`Chunk`].
+#[derive(Default)]
+pub struct Compiler {
+    chunk: Chunk,
+    contexts: Vec<JumpContext>,
+    scope_depth: usize,
+    // How many values statements keep on the stack while running the statements nested in them,
+    // like the iterator of a for-of loop
+    held_values: usize,
+}
+
+impl Compiler {
+    /// Compiles a whole program, the resulting chunk returns the program's completion value.
+    pub fn compile(program: &Block) -> Result<Chunk, CompileError> {
+        let mut compiler = Self::default();
+        program.emit_bytecode(&mut compiler)?;
+        compiler.emit(Instruction::Return);
+        Ok(compiler.chunk)
+    }
+
+    /// Compiles the body of a function, to be run in a frame whose scope holds the arguments.
+    /// Unlike a program, running off its end returns undefined.
+    pub fn compile_function(body: &Block) -> Result<Chunk, CompileError> {
+        let mut compiler = Self::default();
+        body.emit_bytecode(&mut compiler)?;
+        compiler.emit(Instruction::Pop);
+        compiler.emit(Instruction::Undefined);
+        compiler.emit(Instruction::Return);
+        Ok(compiler.chunk)
+    }
+
+    /// Appends an instruction, returning its index.
+    pub fn emit(&mut self, instruction: Instruction) -> usize {
+        self.chunk.code.push(instruction);
+        self.chunk.code.len() - 1
+    }
+
+    /// Index of the next instruction to be emitted.
+    pub fn position(&self) -> usize {
+        self.chunk.code.len()
+    }
+
+    /// Points the jump at `jump` to the next instruction to be emitted.
+    pub fn patch_jump(&mut self, jump: usize) {
+        let target = self.position();
+        match &mut self.chunk.code[jump] {
+            Instruction::Jump(to)
+            | Instruction::JumpIfFalse(to)
+            | Instruction::ShortCircuit(_, to)
+            | Instruction::IteratorValue(to)
+            | Instruction::PushHandler(to) => *to = target,
+            _ => panic!("Instruction {} is not a jump", jump),
+        }
+    }
+
+    pub fn constant(&mut self, value: Value) -> usize {
+        self.chunk.constants.push(value);
+        self.chunk.constants.len() - 1
+    }
+
+    pub fn name(&mut self, name: &str) -> usize {
+        match self.chunk.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.chunk.names.push(name.to_owned());
+                self.chunk.names.len() - 1
+            }
+        }
+    }
+
+    pub fn shape(&mut self, keys: Vec<String>) -> usize {
+        self.chunk.shapes.push(keys);
+        self.chunk.shapes.len() - 1
+    }
+
+    /// Compiles a function body on its own, returning the index of its template.
+    pub fn function(
+        &mut self,
+        name: Option<String>,
+        parameters: Vec<String>,
+        body: &Block,
+        is_arrow: bool,
+    ) -> Result<usize, CompileError> {
+        let chunk = Rc::new(Self::compile_function(body)?);
+        self.chunk.functions.push(Rc::new(FunctionTemplate {
+            name,
+            parameters,
+            body: body.clone(),
+            chunk,
+            is_arrow,
+        }));
+        Ok(self.chunk.functions.len() - 1)
+    }
+
+    pub fn class(&mut self, definition: Rc<ClassDefinition>) -> usize {
+        self.chunk.classes.push(definition);
+        self.chunk.classes.len() - 1
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.scope_depth += 1;
+        self.emit(Instruction::EnterScope);
+    }
+
+    pub fn leave_scope(&mut self) {
+        self.scope_depth -= 1;
+        self.emit(Instruction::LeaveScope);
+    }
+
+    /// Marks the value on top of the stack as kept there while the statements that follow run,
+    /// a break or continue out of them drops it.
+    pub fn hold_value(&mut self) {
+        self.held_values += 1;
+    }
+
+    /// Marks the value held last as no longer kept on the stack.
+    pub fn release_value(&mut self) {
+        self.held_values -= 1;
+    }
+
+    /// Starts a loop, which may also be the target of a labeled break or continue.
+    pub fn begin_loop(&mut self, labels: &[String]) {
+        self.begin_context(labels, ContextKind::Loop);
+    }
+
+    /// Points every break of the innermost loop at the next instruction to be emitted.
+    pub fn end_loop(&mut self) {
+        self.end_context();
+    }
+
+    /// Points every continue of the innermost loop at the next instruction to be emitted, which
+    /// is where the next iteration starts.
+    pub fn patch_continues(&mut self) {
+        let context = self.contexts.last_mut().expect("Not in a loop");
+        for jump in std::mem::take(&mut context.continues) {
+            self.patch_jump(jump);
+        }
+    }
+
+    /// Starts a labeled statement that isn't a loop, which only a labeled break can leave.
+    pub fn begin_labeled(&mut self, labels: &[String]) {
+        self.begin_context(labels, ContextKind::Labeled);
+    }
+
+    /// Points every break out of the innermost labeled statement at the next instruction to be
+    /// emitted.
+    pub fn end_labeled(&mut self) {
+        self.end_context();
+    }
+
+    /// Starts a switch, which a break without a label leaves, while a continue goes on to the
+    /// enclosing loop.
+    pub fn begin_switch(&mut self) {
+        self.begin_context(&[], ContextKind::Switch);
+    }
+
+    /// Points every break out of the innermost switch at the next instruction to be emitted.
+    pub fn end_switch(&mut self) {
+        self.end_context();
+    }
+
+    /// Starts the part of a try statement that runs with a handler pushed. A break, continue or
+    /// return out of it pops the handler and runs the finally block, if there is one.
+    pub fn begin_try(&mut self, finalizer: Option<&Block>) {
+        self.begin_context(&[], ContextKind::Try);
+        self.contexts.last_mut().unwrap().finalizer = finalizer.cloned();
+    }
+
+    /// Ends the part of a try statement started last, a finally block emitted after it runs
+    /// outside of it.
+    pub fn end_try(&mut self) {
+        let context = self.contexts.pop().expect("Not in a try statement");
+        debug_assert!(context.kind == ContextKind::Try);
+    }
+
+    /// Runs the finally blocks of every try statement the function is in, and returns the value
+    /// on top of the stack.
+    pub fn emit_return(&mut self) -> Result<(), CompileError> {
+        let (scope_depth, held_values) = (self.scope_depth, self.held_values);
+        self.emit_finalizers(0, true)?;
+        self.emit(Instruction::Return);
+        self.scope_depth = scope_depth;
+        self.held_values = held_values;
+        Ok(())
+    }
+
+    /// Leaves every scope entered since the statement the break targets started, and jumps past
+    /// its end.
+    pub fn emit_break(&mut self, label: Option<&str>) -> Result<(), CompileError> {
+        let target = self.jump_target(label, true)?;
+        let jump = self.emit_unwinding_jump(target)?;
+        self.contexts[target].breaks.push(jump);
+        Ok(())
+    }
+
+    /// Leaves every scope entered since the loop the continue targets started, and jumps to its
+    /// next iteration.
+    pub fn emit_continue(&mut self, label: Option<&str>) -> Result<(), CompileError> {
+        let target = self.jump_target(label, false)?;
+        if self.contexts[target].kind != ContextKind::Loop {
+            return Err(CompileError::Invalid(format!(
+                "Continue targets '{}', which isn't a loop",
+                label.unwrap_or_default()
+            )));
+        }
+        let jump = self.emit_unwinding_jump(target)?;
+        self.contexts[target].continues.push(jump);
+        Ok(())
+    }
+
+    fn begin_context(&mut self, labels: &[String], kind: ContextKind) {
+        self.contexts.push(JumpContext {
+            labels: labels.to_vec(),
+            kind,
+            scope_depth: self.scope_depth,
+            held_values: self.held_values,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+            finalizer: None,
+        });
+    }
+
+    fn end_context(&mut self) {
+        let context = self
+            .contexts
+            .pop()
+            .expect("Not in a loop, switch or labeled statement");
+        debug_assert!(context.kind != ContextKind::Try);
+        for jump in context.breaks {
+            self.patch_jump(jump);
+        }
+    }
+
+    // The innermost loop for an unlabeled jump, or switch for an unlabeled break, the innermost
+    // statement with the label otherwise
+    fn jump_target(&self, label: Option<&str>, is_break: bool) -> Result<usize, CompileError> {
+        let target = self.contexts.iter().rposition(|context| match label {
+            None => {
+                context.kind == ContextKind::Loop
+                    || (is_break && context.kind == ContextKind::Switch)
+            }
+            Some(label) => context.labels.iter().any(|l| l == label),
+        });
+        target.ok_or_else(|| {
+            CompileError::Invalid(match (label, is_break) {
+                (None, true) => "Break outside of a loop or switch".to_owned(),
+                (None, false) => "Continue outside of a loop".to_owned(),
+                (Some(label), _) => format!("Undefined label '{}'", label),
+            })
+        })
+    }
+
+    fn emit_unwinding_jump(&mut self, target: usize) -> Result<usize, CompileError> {
+        let (scope_depth, held_values) = (self.scope_depth, self.held_values);
+        self.emit_finalizers(target + 1, false)?;
+        for _ in self.contexts[target].scope_depth..self.scope_depth {
+            self.emit(Instruction::LeaveScope);
+        }
+        for _ in self.contexts[target].held_values..self.held_values {
+            self.emit(Instruction::Pop);
+        }
+        let jump = self.emit(Instruction::Jump(0));
+        // The code after the jump is still in the scopes the jump left
+        self.scope_depth = scope_depth;
+        self.held_values = held_values;
+        Ok(jump)
+    }
+
+    // Leaves the try blocks from the innermost one out to the context at `outer`, popping their
+    // handlers and running their finally blocks in the scope the try statement is in. A return
+    // keeps its value on top of the stack instead of dropping the held values below it.
+    fn emit_finalizers(&mut self, outer: usize, keep_value: bool) -> Result<(), CompileError> {
+        for i in (outer..self.contexts.len()).rev() {
+            if self.contexts[i].kind != ContextKind::Try {
+                continue;
+            }
+            for _ in self.contexts[i].scope_depth..self.scope_depth {
+                self.emit(Instruction::LeaveScope);
+            }
+            self.scope_depth = self.contexts[i].scope_depth;
+            if !keep_value {
+                for _ in self.contexts[i].held_values..self.held_values {
+                    self.emit(Instruction::Pop);
+                }
+                self.held_values = self.contexts[i].held_values;
+            }
+            self.emit(Instruction::PopHandler);
+
+            if let Some(finalizer) = self.contexts[i].finalizer.clone() {
+                // A jump in the finally block only leaves the statements around the try statement
+                let inner = self.contexts.split_off(i);
+                if keep_value {
+                    self.hold_value();
+                }
+                finalizer.emit_bytecode(self)?;
+                self.emit(Instruction::Pop);
+                if keep_value {
+                    self.release_value();
+                }
+                self.contexts.extend(inner);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Classes, lowered onto the same constructor functions and prototype objects that scripts can
+//! build by hand, so that classes and plain constructor functions can extend each other.
+//!
+//! Methods find their way to `super` through the scope they close over, which binds
+//! [`HOME_OBJECT`] to the object they were defined on: the class itself for static methods, and
+//! its prototype for everything else. `super` is whatever that object inherits from.
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    ir::{
+        expression::{SuperExpression, Variable},
+        statement::{Block, BlockType, ExpressionStatement},
+    },
+    runtime::{
+        exception::*, Accessor, Environment, Function, Handle, Interpreter, ObjectType, Properties,
+        Scope, Value,
+    },
+};
+
+/// The variable that holds the object a method was defined on. It isn't a valid identifier, so
+/// scripts can't refer to it.
+pub const HOME_OBJECT: &str = "%home";
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum MemberKind {
+    Method,
+    Getter,
+    Setter,
+}
+
+/// A method, getter or setter of a class body.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub struct ClassMember {
+    pub kind: MemberKind,
+    pub is_static: bool,
+    pub key: String,
+    pub parameters: Vec<String>,
+    pub body: Block,
+}
+
+/// Everything about a class that is known before running it, the class it extends is only known
+/// once its expression is evaluated.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone)]
+pub struct ClassDefinition {
+    pub name: Option<String>,
+    pub has_superclass: bool,
+    pub constructor: Option<ClassMember>,
+    pub members: Vec<ClassMember>,
+}
+
+impl ClassDefinition {
+    /// Creates the constructor function of the class, with its methods in place, extending
+    /// `parent` if the class has a superclass. Methods close over `environment`.
+    pub fn create(
+        &self,
+        interpreter: &mut Interpreter,
+        parent: Option<Value>,
+        environment: Scope,
+    ) -> Result<Value, Exception> {
+        let (parent, parent_prototype) = match parent {
+            None => (None, interpreter.object_prototype()),
+            Some(Value::Object(o)) if o.borrow().get_type() == ObjectType::Function => {
+                let prototype = o.borrow().lookup("prototype");
+                match prototype {
+                    Some(Value::Object(prototype)) => (Some(o), prototype),
+                    _ => bail!(TypeError(
+                        "Class extends value does not have valid prototype property".to_owned()
+                    )),
+                }
+            }
+            Some(_) => bail!(TypeError(
+                "Class extends value is not a constructor".to_owned()
+            )),
+        };
+
+        let prototype =
+            handle(interpreter.new_object_with(Properties::new(), Some(parent_prototype)));
+
+        // The class can refer to itself by name, whatever happens to the binding outside
+        let class_scope = Environment::new(HashMap::new(), Some(environment));
+        let instance_scope = Environment::new(HashMap::new(), Some(Rc::clone(&class_scope)));
+        let static_scope = Environment::new(HashMap::new(), Some(Rc::clone(&class_scope)));
+
+        let (parameters, body) = match (&self.constructor, &parent) {
+            (Some(constructor), _) => (constructor.parameters.clone(), constructor.body.clone()),
+            (None, None) => (Vec::new(), Block::new(BlockType::Function)),
+            (None, Some(parent)) => default_derived_constructor(parent),
+        };
+        let mut constructor = Function::class_constructor(self.name.clone(), parameters, body);
+        constructor.environment = Some(Rc::clone(&instance_scope));
+        let class = handle(interpreter.new_function(constructor));
+
+        // Replaces the prototype every function gets, with one that extends the parent's
+        class
+            .borrow_mut()
+            .put_hidden("prototype", Value::Object(Rc::clone(&prototype)));
+        prototype
+            .borrow_mut()
+            .put_hidden("constructor", Value::Object(Rc::clone(&class)));
+        if let Some(parent) = parent {
+            // Static methods are inherited too
+            class.borrow_mut().set_prototype(Some(parent));
+        }
+
+        let home = |object: &Handle| Value::Object(Rc::clone(object));
+        instance_scope
+            .borrow_mut()
+            .declare(HOME_OBJECT.to_owned(), home(&prototype));
+        static_scope
+            .borrow_mut()
+            .declare(HOME_OBJECT.to_owned(), home(&class));
+        if let Some(name) = self.name.as_ref() {
+            class_scope.borrow_mut().declare(name.clone(), home(&class));
+        }
+
+        for member in self.members.iter() {
+            let (target, scope) = match member.is_static {
+                true => (&class, &static_scope),
+                false => (&prototype, &instance_scope),
+            };
+            let mut function = Function::new(
+                Some(member.key.clone()),
+                member.parameters.clone(),
+                member.body.clone(),
+            );
+            function.environment = Some(Rc::clone(scope));
+            let function = interpreter.new_method(function);
+
+            if member.kind == MemberKind::Method {
+                target.borrow_mut().put_hidden(&member.key, function);
+                continue;
+            }
+
+            // A getter and a setter of the same name share one accessor
+            let existing = target.borrow().get(&member.key).cloned();
+            let accessor = match existing {
+                Some(Value::Object(o)) if o.borrow().get_type() == ObjectType::Accessor => o,
+                _ => {
+                    let accessor = interpreter.allocate(Accessor::boxed());
+                    target
+                        .borrow_mut()
+                        .put_hidden(&member.key, Value::Object(Rc::clone(&accessor)));
+                    accessor
+                }
+            };
+            let mut accessor = accessor.borrow_mut();
+            let accessor = accessor.as_accessor();
+            match member.kind {
+                MemberKind::Getter => accessor.getter = Some(handle(function)),
+                _ => accessor.setter = Some(handle(function)),
+            }
+        }
+
+        Ok(Value::Object(class))
+    }
+}
+
+/// The object methods running in `scope` were defined on, undefined outside of methods.
+pub fn home_object(scope: &Scope) -> Value {
+    Environment::lookup(scope, HOME_OBJECT).unwrap_or_default()
+}
+
+/// The object `super.name` looks `name` up in, which is what the home object of the running
+/// method inherits from.
+pub fn super_base(home: &Value) -> Result<Handle, Exception> {
+    match home {
+        Value::Object(o) => o
+            .borrow()
+            .prototype()
+            .ok_or_else(|| TypeError("Super has no prototype".to_owned())),
+        _ => Err(Exception::Exception(
+            "\'super\' keyword unexpected here".to_owned(),
+        )),
+    }
+}
+
+/// The constructor `super(...)` calls, the one the class of the running constructor extends.
+pub fn super_constructor(home: &Value) -> Result<Value, Exception> {
+    let class = match home {
+        Value::Object(o) => o.borrow().lookup("constructor"),
+        _ => None,
+    };
+    let parent = match class {
+        Some(Value::Object(class)) => class.borrow().prototype(),
+        _ => bail!(Exception::Exception(
+            "\'super\' keyword unexpected here".to_owned()
+        )),
+    };
+    match parent {
+        Some(parent) if parent.borrow().get_type() == ObjectType::Function => {
+            Ok(Value::Object(parent))
+        }
+        _ => Err(TypeError(
+            "Super constructor is not a constructor".to_owned(),
+        )),
+    }
+}
+
+/// A class that extends another and has no constructor of its own passes its arguments on to
+/// the parent's constructor, which takes the parent's parameters.
+fn default_derived_constructor(parent: &Handle) -> (Vec<String>, Block) {
+    let parameters = parent.borrow_mut().as_function().parameters.clone();
+    let arguments = parameters
+        .iter()
+        .map(|parameter| Variable::boxed(parameter) as _)
+        .collect();
+
+    let mut body = Block::new(BlockType::Function);
+    body.push(ExpressionStatement::boxed(SuperExpression::boxed_call(
+        arguments,
+    )));
+    (parameters, body)
+}
+
+fn handle(value: Value) -> Handle {
+    match value {
+        Value::Object(o) => o,
+        _ => unreachable!("Expected a newly created object"),
+    }
+}
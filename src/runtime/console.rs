@@ -1,7 +1,7 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashSet, fmt, rc::Rc};
 
 use crate::{
-    runtime::{Function, Object, Value},
+    runtime::{object, Function, Handle, Keys, Object, Properties, Prototype, Trace, Value},
     util::*,
 };
 
@@ -10,12 +10,13 @@ use crate::{
 #[object_type(Object)]
 pub struct Console {
     #[properties]
-    properties: HashMap<String, Value>,
+    properties: Properties,
+    prototype: Option<Handle>,
 }
 
 impl Console {
     pub fn new() -> Self {
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
 
         let log_function_built_in =
             Function::built_in(Some("console_log".to_string()), vec!["expr".to_string()]);
@@ -24,7 +25,10 @@ impl Console {
 
         properties.insert("log".to_string(), log_function_object);
 
-        Self { properties }
+        Self {
+            properties,
+            prototype: None,
+        }
     }
 }
 
@@ -39,3 +43,33 @@ impl fmt::Display for Console {
         f.write_str(&self.format_properties())
     }
 }
+
+impl Keys for Console {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &HashSet::new())
+    }
+}
+
+impl Prototype for Console {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for Console {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+    }
+}
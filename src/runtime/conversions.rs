@@ -0,0 +1,265 @@
+//! The type conversions and equality comparisons of ECMAScript, which operators apply to their
+//! operands. Converting an object calls its `valueOf` or `toString` method, so most of them need
+//! the interpreter.
+use std::rc::Rc;
+
+use crate::runtime::{exception::*, Interpreter, ObjectType, Value};
+
+/// The type an object should preferably be converted to by [`to_primitive`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Default,
+    Number,
+    String,
+}
+
+/// Converts `value` to a primitive, objects are asked for one through their `valueOf` and
+/// `toString` methods, in the order `hint` prefers.
+pub fn to_primitive(
+    interpreter: &mut Interpreter,
+    value: Value,
+    hint: Hint,
+) -> Result<Value, Exception> {
+    let object = match &value {
+        Value::Object(o) => Rc::clone(o),
+        _ => return Ok(value),
+    };
+
+    let methods = match hint {
+        Hint::String => ["toString", "valueOf"],
+        Hint::Default | Hint::Number => ["valueOf", "toString"],
+    };
+    for name in methods {
+        if object.borrow().lookup(name).is_none() {
+            continue;
+        }
+        let method = match interpreter.get_property(&object, name, value.clone())? {
+            Value::Object(method) if method.borrow().get_type() == ObjectType::Function => method,
+            _ => continue,
+        };
+        match interpreter.call_function(&method, value.clone(), Vec::new())? {
+            Value::Object(_) => continue,
+            primitive => return Ok(primitive),
+        }
+    }
+    Err(TypeError(
+        "Cannot convert object to primitive value".to_owned(),
+    ))
+}
+
+pub fn to_boolean(value: &Value) -> bool {
+    match value {
+        Value::Number(n) => !(*n == 0f64 || n.is_nan()),
+        Value::Undefined | Value::Null => false,
+        Value::Boolean(b) => *b,
+        Value::StringLiteral(s) => !s.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+pub fn to_number(interpreter: &mut Interpreter, value: Value) -> Result<f64, Exception> {
+    Ok(match value {
+        Value::Number(n) => n,
+        Value::Undefined => f64::NAN,
+        Value::Null => 0f64,
+        Value::Boolean(b) => b as u8 as f64,
+        Value::StringLiteral(s) => string_to_number(&s),
+        object @ Value::Object(_) => {
+            let primitive = to_primitive(interpreter, object, Hint::Number)?;
+            return to_number(interpreter, primitive);
+        }
+    })
+}
+
+pub fn to_string(interpreter: &mut Interpreter, value: Value) -> Result<String, Exception> {
+    Ok(match value {
+        Value::Number(n) => number_to_string(n),
+        Value::Undefined => String::from("undefined"),
+        Value::Null => String::from("null"),
+        Value::Boolean(b) => b.to_string(),
+        Value::StringLiteral(s) => s,
+        object @ Value::Object(_) => {
+            let primitive = to_primitive(interpreter, object, Hint::String)?;
+            return to_string(interpreter, primitive);
+        }
+    })
+}
+
+/// Converts `value` to a number, wrapped to a 32-bit signed integer like the bitwise operators
+/// need.
+pub fn to_int32(interpreter: &mut Interpreter, value: Value) -> Result<i32, Exception> {
+    Ok(to_uint32(interpreter, value)? as i32)
+}
+
+/// Converts `value` to a number, wrapped to a 32-bit unsigned integer like `>>>` and shift
+/// counts need.
+pub fn to_uint32(interpreter: &mut Interpreter, value: Value) -> Result<u32, Exception> {
+    let n = to_number(interpreter, value)?;
+    if !n.is_finite() {
+        return Ok(0);
+    }
+    Ok(n.trunc().rem_euclid(4294967296f64) as u32)
+}
+
+/// Parses a string the way `Number("...")` does, anything that isn't a numeric literal
+/// surrounded by whitespace is NaN.
+pub fn string_to_number(s: &str) -> f64 {
+    let s = s.trim();
+    if s.is_empty() {
+        return 0f64;
+    }
+
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => Some(16),
+        Some("0o") | Some("0O") => Some(8),
+        Some("0b") | Some("0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let digits = &s[2..];
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return f64::NAN;
+        }
+        return digits.chars().fold(0f64, |n, c| {
+            n * radix as f64 + c.to_digit(radix).unwrap() as f64
+        });
+    }
+
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if unsigned == "Infinity" {
+        return if s.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+    // Rust would also accept "inf" and "NaN"
+    let is_literal = unsigned
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    match is_literal {
+        true => s.parse().unwrap_or(f64::NAN),
+        false => f64::NAN,
+    }
+}
+
+/// Formats a number the way `String(n)` does, in plain notation unless the number is very large
+/// or very small.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        return String::from("NaN");
+    }
+    if n == 0f64 {
+        return String::from("0");
+    }
+    if n.is_infinite() {
+        return String::from(if n > 0f64 { "Infinity" } else { "-Infinity" });
+    }
+    if n < 0f64 {
+        return format!("-{}", number_to_string(-n));
+    }
+
+    // The shortest digits that round trip, and where the decimal point goes relative to them
+    let scientific = format!("{:e}", n);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let length = digits.len() as i32;
+    let point = exponent.parse::<i32>().unwrap() + 1;
+
+    if length <= point && point <= 21 {
+        digits + &"0".repeat((point - length) as usize)
+    } else if 0 < point && point <= 21 {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let sign = if point > 0 { '+' } else { '-' };
+        let exponent = (point - 1).abs();
+        match length {
+            1 => format!("{}e{}{}", digits, sign, exponent),
+            _ => format!("{}.{}e{}{}", &digits[..1], &digits[1..], sign, exponent),
+        }
+    }
+}
+
+/// `===`, values of different types are never equal, and objects are only equal to themselves.
+pub fn strict_equals(lhs: &Value, rhs: &Value) -> bool {
+    // Which is what comparing values in Rust does
+    lhs == rhs
+}
+
+/// `==`, which converts operands of different types until they can be compared strictly.
+pub fn abstract_equals(
+    interpreter: &mut Interpreter,
+    lhs: Value,
+    rhs: Value,
+) -> Result<bool, Exception> {
+    use Value::*;
+    Ok(match (lhs, rhs) {
+        // Null and undefined are only loosely equal to each other
+        (Null | Undefined, other) | (other, Null | Undefined) => {
+            matches!(other, Null | Undefined)
+        }
+        (Number(a), StringLiteral(b)) => a == string_to_number(&b),
+        (StringLiteral(a), Number(b)) => string_to_number(&a) == b,
+        (Boolean(a), other) => abstract_equals(interpreter, Number(a as u8 as f64), other)?,
+        (other, Boolean(b)) => abstract_equals(interpreter, other, Number(b as u8 as f64))?,
+        (object @ Object(_), primitive @ (Number(_) | StringLiteral(_)))
+        | (primitive @ (Number(_) | StringLiteral(_)), object @ Object(_)) => {
+            let converted = to_primitive(interpreter, object, Hint::Default)?;
+            abstract_equals(interpreter, primitive, converted)?
+        }
+        (lhs, rhs) => strict_equals(&lhs, &rhs),
+    })
+}
+
+/// Whether `lhs < rhs`, for operands already converted to primitives. Strings are compared by
+/// their UTF-16 code units, anything else as numbers, where NaN is unordered.
+pub fn less_than(
+    interpreter: &mut Interpreter,
+    lhs: Value,
+    rhs: Value,
+) -> Result<Option<bool>, Exception> {
+    if let (Value::StringLiteral(a), Value::StringLiteral(b)) = (&lhs, &rhs) {
+        return Ok(Some(a.encode_utf16().lt(b.encode_utf16())));
+    }
+    let a = to_number(interpreter, lhs)?;
+    let b = to_number(interpreter, rhs)?;
+    match a.is_nan() || b.is_nan() {
+        true => Ok(None),
+        false => Ok(Some(a < b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_format_like_javascript() {
+        assert_eq!(number_to_string(42f64), "42");
+        assert_eq!(number_to_string(-1.5), "-1.5");
+        assert_eq!(number_to_string(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(number_to_string(1e21), "1e+21");
+        assert_eq!(number_to_string(1.5e300), "1.5e+300");
+        assert_eq!(number_to_string(1e-7), "1e-7");
+        assert_eq!(number_to_string(0.000001), "0.000001");
+        assert_eq!(number_to_string(-0f64), "0");
+        assert_eq!(number_to_string(f64::NEG_INFINITY), "-Infinity");
+    }
+
+    #[test]
+    fn strings_parse_like_javascript() {
+        assert_eq!(string_to_number("  12.5\n"), 12.5);
+        assert_eq!(string_to_number(""), 0f64);
+        assert_eq!(string_to_number("0x1F"), 31f64);
+        assert_eq!(string_to_number("-Infinity"), f64::NEG_INFINITY);
+        assert!(string_to_number("inf").is_nan());
+        assert!(string_to_number("12px").is_nan());
+        assert!(string_to_number("-0x10").is_nan());
+    }
+}
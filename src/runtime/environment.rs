@@ -0,0 +1,194 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+
+use crate::runtime::{exception::*, Trace, Value};
+
+/// A shared reference to an [`Environment`], functions hold on to the one they were created in.
+pub type Scope = Rc<RefCell<Environment>>;
+
+/// The bindings of a single scope, along with the scope it is nested in. Variables that aren't
+/// bound here are looked up in the parent, so a chain of environments is what a piece of code
+/// can see, independent of who called it.
+#[derive(Default)]
+pub struct Environment {
+    bindings: HashMap<String, Value>,
+    parent: Option<Scope>,
+    // The `let`, `const` and class bindings whose declaration hasn't run yet, using them is an
+    // error until it does
+    uninitialized: HashSet<String>,
+    // The bindings that can't be assigned to, those declared with `const`
+    constants: HashSet<String>,
+    // The bindings declared with `var`, which functions declared in nested blocks are assigned to
+    vars: HashSet<String>,
+}
+
+impl Environment {
+    pub fn new(bindings: HashMap<String, Value>, parent: Option<Scope>) -> Scope {
+        Rc::new(RefCell::new(Self {
+            bindings,
+            parent,
+            uninitialized: HashSet::new(),
+            constants: HashSet::new(),
+            vars: HashSet::new(),
+        }))
+    }
+
+    pub fn parent(&self) -> Option<Scope> {
+        self.parent.clone()
+    }
+
+    /// A new environment with the same bindings and parent as `scope`, so that closures created
+    /// in one iteration of a loop keep the values of that iteration.
+    pub fn copy(scope: &Scope) -> Scope {
+        let environment = scope.borrow();
+        Rc::new(RefCell::new(Self {
+            bindings: environment.bindings.clone(),
+            parent: environment.parent(),
+            uninitialized: environment.uninitialized.clone(),
+            constants: environment.constants.clone(),
+            vars: environment.vars.clone(),
+        }))
+    }
+
+    /// Binds `name` to `value`, replacing whatever this environment bound it to before.
+    pub fn declare(&mut self, name: String, value: Value) {
+        self.uninitialized.remove(&name);
+        self.constants.remove(&name);
+        self.bindings.insert(name, value);
+    }
+
+    /// Binds `name` to `value` for good, assigning to it is an error.
+    pub fn declare_constant(&mut self, name: String, value: Value) {
+        self.constants.insert(name.clone());
+        self.uninitialized.remove(&name);
+        self.bindings.insert(name, value);
+    }
+
+    /// Binds a `var` to undefined, unless this environment binds it already, e.g. to an argument.
+    pub fn hoist_var(&mut self, name: &str) {
+        if !self.bindings.contains_key(name) {
+            self.bindings.insert(name.to_owned(), Value::Undefined);
+            self.vars.insert(name.to_owned());
+        }
+    }
+
+    /// Binds a `let`, `const` or class before its declaration runs, so that it shadows outer
+    /// bindings of the same name, but can't be used until [`Environment::declare`] initializes
+    /// it.
+    pub fn hoist_lexical(&mut self, name: &str) {
+        self.bindings.insert(name.to_owned(), Value::Undefined);
+        self.uninitialized.insert(name.to_owned());
+        self.constants.remove(name);
+        self.vars.remove(name);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.bindings.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.bindings.get_mut(name)
+    }
+
+    /// Finds the innermost environment in the chain starting at `scope` that binds `name`.
+    pub fn resolve(scope: &Scope, name: &str) -> Option<Scope> {
+        let mut current = Some(Rc::clone(scope));
+        while let Some(scope) = current {
+            if scope.borrow().bindings.contains_key(name) {
+                return Some(scope);
+            }
+            current = scope.borrow().parent();
+        }
+        None
+    }
+
+    /// The value bound to `name` in the chain starting at `scope`.
+    pub fn lookup(scope: &Scope, name: &str) -> Option<Value> {
+        Self::resolve(scope, name).and_then(|scope| scope.borrow().get(name).cloned())
+    }
+
+    /// Same as [`Environment::lookup`], failing for a binding whose declaration hasn't run yet.
+    pub fn read(scope: &Scope, name: &str) -> Result<Option<Value>, Exception> {
+        let scope = match Self::resolve(scope, name) {
+            Some(scope) => scope,
+            None => return Ok(None),
+        };
+        let environment = scope.borrow();
+        if environment.uninitialized.contains(name) {
+            bail!(UninitializedError(name.to_owned()));
+        }
+        Ok(environment.get(name).cloned())
+    }
+
+    /// Assigns `value` to the binding of `name` in the chain starting at `scope`, if it is a
+    /// `var`. That is how a function declared in a block is also bound outside of it, but never
+    /// in place of a parameter or a `let` of the same name. Returns whether the chain binds
+    /// `name` at all, top level vars aren't bound in a scope.
+    pub fn write_var(scope: &Scope, name: &str, value: Value) -> bool {
+        match Self::resolve(scope, name) {
+            Some(scope) => {
+                let mut environment = scope.borrow_mut();
+                if environment.vars.contains(name) {
+                    environment.bindings.insert(name.to_owned(), value);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Assigns `value` to the binding of `name` in the chain starting at `scope`, returning
+    /// whether there is one. Constants and bindings whose declaration hasn't run yet can't be
+    /// assigned to.
+    pub fn write(scope: &Scope, name: &str, value: Value) -> Result<bool, Exception> {
+        let scope = match Self::resolve(scope, name) {
+            Some(scope) => scope,
+            None => return Ok(false),
+        };
+        let mut environment = scope.borrow_mut();
+        if environment.uninitialized.contains(name) {
+            bail!(UninitializedError(name.to_owned()));
+        }
+        if environment.constants.contains(name) {
+            bail!(TypeError(format!(
+                "Assignment to constant variable \"{}\"",
+                name
+            )));
+        }
+        environment.bindings.insert(name.to_owned(), value);
+        Ok(true)
+    }
+}
+
+impl Trace for Environment {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.bindings.values().for_each(&mut *visit);
+        let mut current = self.parent();
+        while let Some(scope) = current {
+            let environment = scope.borrow();
+            environment.bindings.values().for_each(&mut *visit);
+            current = environment.parent();
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.bindings.clear();
+        self.uninitialized.clear();
+        self.constants.clear();
+        self.parent = None;
+    }
+}
+
+// Bindings can refer back to the environment through closures, so only the names are printed
+impl fmt::Debug for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Environment")
+            .field("bindings", &self.bindings.keys().collect::<Vec<_>>())
+            .field("parent", &self.parent.is_some())
+            .finish()
+    }
+}
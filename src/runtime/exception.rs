@@ -2,6 +2,8 @@ use std::fmt::Debug;
 
 use thiserror::Error;
 
+use crate::runtime::{Interpreter, Properties, Value};
+
 pub use crate::bail;
 pub use crate::exception;
 pub use crate::success;
@@ -9,14 +11,42 @@ pub use crate::success;
 pub use self::Exception::*;
 
 #[cfg_attr(debug_assertions, derive(Debug))]
-#[derive(Error, Clone, Eq, PartialEq)]
+#[derive(Error, Clone, PartialEq)]
 pub enum Exception {
     #[error("{0}")]
     Exception(String),
     #[error("{0}")]
     TypeError(String),
+    #[error("{0}")]
+    RangeError(String),
     #[error("Cannot find variable \"{0}\"")]
     ReferenceError(String),
+    /// The ReferenceError for using a `let`, `const` or class binding before its declaration ran
+    #[error("Cannot access \"{0}\" before initialization")]
+    UninitializedError(String),
+    /// A value thrown by a script with a `throw` statement
+    #[error("{0}")]
+    Thrown(Value),
+}
+
+impl Exception {
+    /// The value a `catch` clause binds for this exception. Thrown values are caught as they
+    /// were thrown, exceptions raised by the runtime become error objects with a `name` and a
+    /// `message`, like the built-in errors of JavaScript.
+    pub fn into_value(self, interpreter: &mut Interpreter) -> Value {
+        let name = match &self {
+            Exception::Thrown(value) => return value.clone(),
+            Exception::Exception(_) => "Error",
+            Exception::TypeError(_) => "TypeError",
+            Exception::RangeError(_) => "RangeError",
+            Exception::ReferenceError(_) | Exception::UninitializedError(_) => "ReferenceError",
+        };
+
+        let mut properties = Properties::new();
+        properties.insert("name".to_owned(), Value::StringLiteral(name.to_owned()));
+        properties.insert("message".to_owned(), Value::StringLiteral(self.to_string()));
+        interpreter.new_object(properties)
+    }
 }
 
 #[macro_export]
@@ -1,8 +1,10 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashSet, fmt, rc::Rc};
 
 use crate::{
     ir::statement::{Block, BlockType},
-    runtime::{Object, Value},
+    runtime::{
+        bytecode::Chunk, object, Handle, Keys, Object, Properties, Prototype, Scope, Trace, Value,
+    },
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -12,42 +14,139 @@ pub struct Function {
     // TODO: Properties should contain the arguments, caller, callee
     //       and number of args while the function is executing as per
     //       https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Functions#the_arguments_object
-    properties: HashMap<String, Value>,
+    properties: Properties,
+    prototype: Option<Handle>,
+    // The keys of the properties that aren't enumerated
+    hidden: HashSet<String>,
     pub name: Option<String>,
     pub parameters: Vec<String>,
     pub body: Block,
+    // The environment the function was created in, free variables of the body resolve through it
+    pub environment: Option<Scope>,
+    // The body compiled for the VM, filled in when first called there if not compiled up front
+    pub bytecode: Option<Rc<Chunk>>,
     is_built_in: bool,
+    // Arrow functions see the `this` and `arguments` of the function they were created in
+    is_arrow: bool,
+    // The constructor of a class can only be run by `new`, or by `super` in a derived class
+    is_class_constructor: bool,
 }
 
 impl Function {
     pub fn new(name: Option<String>, parameters: Vec<String>, body: Block) -> Box<Self> {
         Box::new(Self {
-            properties: HashMap::new(),
+            properties: Properties::new(),
+            prototype: None,
+            hidden: HashSet::new(),
             name,
             parameters,
             body,
+            environment: None,
+            bytecode: None,
             is_built_in: false,
+            is_arrow: false,
+            is_class_constructor: false,
         })
     }
 
+    /// An arrow function, which has no name and can't be used as a constructor.
+    pub fn arrow(parameters: Vec<String>, body: Block) -> Box<Self> {
+        let mut function = Self::new(None, parameters, body);
+        function.is_arrow = true;
+        function
+    }
+
+    /// The constructor of a class, which can't be called without `new`.
+    pub fn class_constructor(
+        name: Option<String>,
+        parameters: Vec<String>,
+        body: Block,
+    ) -> Box<Self> {
+        let mut function = Self::new(name, parameters, body);
+        function.is_class_constructor = true;
+        function
+    }
+
     pub fn built_in(name: Option<String>, parameters: Vec<String>) -> Box<Self> {
         Box::new(Self {
-            properties: HashMap::new(),
+            properties: Properties::new(),
+            prototype: None,
+            hidden: HashSet::new(),
             name,
             parameters,
             body: Block::new(BlockType::Function),
+            environment: None,
+            bytecode: None,
             is_built_in: true,
+            is_arrow: false,
+            is_class_constructor: false,
         })
     }
 
     pub fn is_built_in(&self) -> bool {
         self.is_built_in
     }
+
+    pub fn is_arrow(&self) -> bool {
+        self.is_arrow
+    }
+
+    pub fn is_class_constructor(&self) -> bool {
+        self.is_class_constructor
+    }
 }
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Function Object: {}", self.name.as_ref().unwrap_or(&"Anonymous".into()))?;
-        f.write_str(&self.format_properties())
+        // The properties aren't printed, `prototype` refers back to the function
+        write!(
+            f,
+            "Function Object: {}",
+            self.name.as_ref().unwrap_or(&"Anonymous".into())
+        )
+    }
+}
+
+impl Keys for Function {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &self.hidden)
+    }
+
+    fn hide(&mut self, key: &str) {
+        self.hidden.insert(key.to_owned());
+    }
+
+    fn delete(&mut self, key: &str) -> bool {
+        self.properties.remove(key);
+        self.hidden.remove(key);
+        true
+    }
+}
+
+impl Prototype for Function {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for Function {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+        if let Some(environment) = self.environment.as_ref() {
+            environment.borrow().trace(visit);
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+        self.environment = None;
     }
 }
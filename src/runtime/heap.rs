@@ -0,0 +1,191 @@
+//! Object allocation and garbage collection.
+//!
+//! Objects are handed out as reference counted [`Handle`]s, so anything that stops being
+//! referenced is freed right away. Reference counting can't free cycles though, e.g. an object
+//! with a property pointing back at itself, so the [`Heap`] keeps track of every object it
+//! allocated and periodically traces everything reachable from the roots it is given. Whatever is
+//! still alive but wasn't reached can only be kept alive by a cycle, so it gets unlinked, dropping
+//! its references to other objects, which lets reference counting free the whole cycle.
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    rc::{Rc, Weak},
+};
+
+use crate::runtime::{Object, Value};
+
+/// A reference to an object allocated on the [`Heap`].
+pub type Handle = Rc<RefCell<Box<dyn Object>>>;
+
+// Collect after this many allocations at the least, so small programs never need to
+const INITIAL_THRESHOLD: usize = 1024;
+
+/// Lets the collector find the values an object holds on to.
+pub trait Trace {
+    /// Calls `visit` on every value directly referenced by this object.
+    fn trace(&self, visit: &mut dyn FnMut(&Value));
+
+    /// Drops every value this object references, breaking any cycle it is part of.
+    fn unlink(&mut self);
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub struct HeapStats {
+    /// Objects currently alive
+    pub live: usize,
+    /// Objects allocated over the life of the heap
+    pub allocated: usize,
+    /// Number of collections run
+    pub collections: usize,
+    /// Objects freed by the collector, ones that reference counting couldn't free on its own
+    pub collected: usize,
+}
+
+/// Owns the bookkeeping for every object allocation, and collects the cycles among them.
+pub struct Heap {
+    objects: Vec<Weak<RefCell<Box<dyn Object>>>>,
+    // Allocation count at which `should_collect` starts asking for a collection
+    threshold: usize,
+    stats: HeapStats,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            threshold: INITIAL_THRESHOLD,
+            stats: HeapStats::default(),
+        }
+    }
+
+    /// Moves `object` onto the heap, returning a handle to it.
+    pub fn allocate(&mut self, object: Box<dyn Object>) -> Handle {
+        let handle = Rc::new(RefCell::new(object));
+        self.objects.push(Rc::downgrade(&handle));
+        self.stats.allocated += 1;
+        handle
+    }
+
+    /// Whether enough has been allocated since the last collection that another is worth running.
+    pub fn should_collect(&self) -> bool {
+        self.objects.len() >= self.threshold
+    }
+
+    /// Frees every object not reachable from `roots`, returning how many were freed.
+    ///
+    /// Any handle held somewhere other than the roots is considered garbage if the object isn't
+    /// also reachable from them, so this must only be called when the roots are complete.
+    pub fn collect<'a>(&mut self, roots: impl IntoIterator<Item = &'a Value>) -> usize {
+        let marked = Self::mark(roots);
+
+        // Objects freed by reference counting since the last collection are forgotten here
+        self.objects.retain(|object| object.strong_count() > 0);
+
+        let garbage: Vec<Handle> = self
+            .objects
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|object| !marked.contains(&address(object)))
+            .collect();
+
+        for object in garbage.iter() {
+            object.borrow_mut().unlink();
+        }
+        let collected = garbage.len();
+        drop(garbage);
+
+        self.objects.retain(|object| object.strong_count() > 0);
+        self.threshold = INITIAL_THRESHOLD.max(self.objects.len() * 2);
+        self.stats.collections += 1;
+        self.stats.collected += collected;
+
+        collected
+    }
+
+    pub fn stats(&self) -> HeapStats {
+        HeapStats {
+            live: self
+                .objects
+                .iter()
+                .filter(|object| object.strong_count() > 0)
+                .count(),
+            ..self.stats
+        }
+    }
+
+    /// The addresses of every object reachable from `roots`.
+    fn mark<'a>(roots: impl IntoIterator<Item = &'a Value>) -> HashSet<*const ()> {
+        let mut marked = HashSet::new();
+        let mut pending: Vec<Handle> = roots
+            .into_iter()
+            .filter_map(|root| match root {
+                Value::Object(o) => Some(Rc::clone(o)),
+                _ => None,
+            })
+            .collect();
+
+        while let Some(object) = pending.pop() {
+            if !marked.insert(address(&object)) {
+                continue;
+            }
+            object.borrow().trace(&mut |value| {
+                if let Value::Object(o) = value {
+                    pending.push(Rc::clone(o));
+                }
+            });
+        }
+
+        marked
+    }
+}
+
+fn address(object: &Handle) -> *const () {
+    Rc::as_ptr(object) as *const ()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{LiteralObject, Properties};
+
+    fn object(heap: &mut Heap) -> Handle {
+        heap.allocate(LiteralObject::boxed(Properties::new()))
+    }
+
+    #[test]
+    fn unreachable_cycle_is_collected() {
+        let mut heap = Heap::new();
+        let a = object(&mut heap);
+        let b = object(&mut heap);
+        a.borrow_mut().put("other", Value::Object(Rc::clone(&b)));
+        b.borrow_mut().put("other", Value::Object(Rc::clone(&a)));
+        drop((a, b));
+
+        assert_eq!(heap.stats().live, 2);
+        assert_eq!(heap.collect(&[]), 2);
+        assert_eq!(heap.stats().live, 0);
+    }
+
+    #[test]
+    fn reachable_objects_survive() {
+        let mut heap = Heap::new();
+        let root = object(&mut heap);
+        let child = object(&mut heap);
+        child
+            .borrow_mut()
+            .put("parent", Value::Object(Rc::clone(&root)));
+        root.borrow_mut().put("child", Value::Object(child));
+
+        let roots = [Value::Object(Rc::clone(&root))];
+        assert_eq!(heap.collect(&roots), 0);
+        assert_eq!(heap.stats().live, 2);
+        assert!(root.borrow().get("child").is_some());
+    }
+}
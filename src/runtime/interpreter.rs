@@ -1,9 +1,21 @@
-use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, iter,
+    rc::Rc,
+};
+
+use itertools::{EitherOrBoth, Itertools};
 
 use crate::{
-    ir::statement::{Block, BlockType},
-    runtime::{Console, exception::*, Object, Value},
-    util::*,
+    ir::{
+        statement::{Block, BlockType},
+        IrNode,
+    },
+    runtime::{
+        array, conversions, exception::*, iterator, iterator::ArrayIterator, object, primitive,
+        Accessor, ArrayObject, Console, Environment, Function, Handle, Heap, Keys, LiteralObject,
+        Object, ObjectType, Properties, Prototype, Scope, Trace, Value,
+    },
 };
 
 /// The global object containing top level definitions of built-in functions
@@ -11,7 +23,8 @@ use crate::{
 #[derive(Object, Clone, Default)]
 #[object_type(Global)]
 pub struct GlobalObject {
-    properties: HashMap<String, Value>,
+    properties: Properties,
+    prototype: Option<Handle>,
 }
 
 impl fmt::Display for GlobalObject {
@@ -20,32 +33,90 @@ impl fmt::Display for GlobalObject {
     }
 }
 
+impl Keys for GlobalObject {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &HashSet::new())
+    }
+
+    fn delete(&mut self, key: &str) -> bool {
+        self.properties.remove(key);
+        true
+    }
+}
+
+impl Prototype for GlobalObject {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for GlobalObject {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+    }
+}
+
+/// How the statement that ran last finished. Anything but [`Completion::Normal`] makes the
+/// enclosing statements stop, until it reaches the loop, label or function it completes. A throw
+/// completion is the `Err` of evaluating a node, so it isn't recorded here.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub enum Completion {
+    Normal,
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(Value),
+}
+
 /// The Interpreter in charge of everything, handles all evaluation, printing, built-in functions,
-/// exceptions and (eventually) memory management.
+/// exceptions and memory management.
 pub struct Interpreter {
+    // Every object allocated while running, and the collector for cycles among them
+    heap: Heap,
+
     // The global object, also known as "window" or "globalThis", contains some top level defs
-    global_object: Rc<RefCell<Box<dyn Object>>>,
+    global_object: Handle,
+
+    // The prototypes objects, functions and arrays are created with, also reachable as
+    // `Object.prototype`, `Function.prototype` and `Array.prototype`
+    object_prototype: Handle,
+    function_prototype: Handle,
+    array_prototype: Handle,
 
-    // The stack of scope for variable resolution
-    scope_stack: Vec<HashMap<String, Value>>,
+    // Where the iterators over arrays, which for-of loops use, get their `next` method from
+    array_iterator_prototype: Handle,
 
-    // Flag indicating if the interpreter should break out of its current context
-    break_flag: bool,
+    // Where the properties of strings, numbers and booleans are looked up, primitives aren't
+    // objects with properties of their own
+    string_prototype: Handle,
+    number_prototype: Handle,
+    boolean_prototype: Handle,
 
-    // Flag indicating if the interpreter should return from the current function
-    return_flag: bool,
+    // The innermost scope of the code being run, variables resolve through its chain of parents
+    environment: Scope,
 
-    // Register to hold return values so that expression values can "skip" up arbitrary levels
-    return_register: Option<Value>,
+    // The top level scope, which stays for the life of the interpreter so that top level
+    // bindings survive between runs
+    global_environment: Scope,
 
-    // A counter indicating how many times it was requested of the interpreter to not treat
-    // function declarations as global declarations, useful for functions as properties or variables
-    declaration_suppression_counter: usize,
+    // How the last statement finished, a break, continue or return skips up arbitrary levels
+    // until something consumes it
+    completion: Completion,
 
-    // Just a buffer to hold the initial scope with its useful things from construction
-    // until first call to interpret, at which point it gets moved to the scope stack
-    // as the bottom
-    base_global_scope: HashMap<String, Value>,
+    // The arrays being joined into strings, an array that contains itself is only joined once
+    joining: Vec<Handle>,
 }
 
 impl Default for Interpreter {
@@ -56,7 +127,33 @@ impl Default for Interpreter {
 
 impl Interpreter {
     pub fn new() -> Self {
-        let global_object = GlobalObject::bundled();
+        let mut heap = Heap::new();
+
+        // Everything inherits from the object prototype, which is the end of every chain
+        let object_prototype = heap.allocate(LiteralObject::boxed(Properties::new()));
+        let function_prototype = heap.allocate(LiteralObject::with_prototype(
+            Properties::new(),
+            Some(Rc::clone(&object_prototype)),
+        ));
+        let array_prototype = heap.allocate(ArrayObject::boxed(
+            Vec::new(),
+            Some(Rc::clone(&object_prototype)),
+        ));
+
+        let array_iterator_prototype = heap.allocate(LiteralObject::with_prototype(
+            Properties::new(),
+            Some(Rc::clone(&object_prototype)),
+        ));
+        let [string_prototype, number_prototype, boolean_prototype] = [(); 3].map(|_| {
+            heap.allocate(LiteralObject::with_prototype(
+                Properties::new(),
+                Some(Rc::clone(&object_prototype)),
+            ))
+        });
+
+        let mut global_object = GlobalObject::default();
+        global_object.set_prototype(Some(Rc::clone(&object_prototype)));
+        let global_object = heap.allocate(Box::new(global_object));
 
         // Create the top level scope
         let mut global_scope = HashMap::new();
@@ -70,19 +167,33 @@ impl Interpreter {
             String::from("window"),
             Value::Object(Rc::clone(&global_object)),
         );
+        // Top level code runs with the global object as `this`
+        global_scope.insert(
+            String::from("this"),
+            Value::Object(Rc::clone(&global_object)),
+        );
 
-        // Add base functions to the global object
-        Self::populate_built_ins(Rc::clone(&global_object));
+        let global_environment = Environment::new(global_scope, None);
 
-        Self {
+        let mut interpreter = Self {
+            heap,
             global_object,
-            scope_stack: Vec::new(),
-            break_flag: false,
-            return_flag: false,
-            return_register: None,
-            declaration_suppression_counter: 0,
-            base_global_scope: global_scope,
-        }
+            object_prototype,
+            function_prototype,
+            array_prototype,
+            array_iterator_prototype,
+            string_prototype,
+            number_prototype,
+            boolean_prototype,
+            environment: Rc::clone(&global_environment),
+            global_environment,
+            completion: Completion::Normal,
+            joining: Vec::new(),
+        };
+
+        // Add base functions to the global object
+        interpreter.populate_built_ins();
+        interpreter
     }
 
     pub fn dump_state(&self) {
@@ -95,14 +206,20 @@ impl Interpreter {
         println!("End Global Object State")
     }
 
+    /// Runs `block` in the global scope. The global scope is kept for every run, so top level
+    /// bindings survive between runs, which is what lets the REPL feed the interpreter one entry
+    /// at a time.
     pub fn run(&mut self, block: Block) -> Result<Value, Exception> {
-        match self.run_with(block, self.base_global_scope.clone()) {
-            Ok(value) => Ok(value),
-            Err(e) => {
-                self.handle_exception(e.clone());
-                Err(e)
-            }
+        let result = self.run_quietly(block);
+        if let Err(e) = &result {
+            self.handle_exception(e.clone());
         }
+        result
+    }
+
+    /// Like [`Interpreter::run`], but leaves reporting an uncaught exception to the caller.
+    pub fn run_quietly(&mut self, mut block: Block) -> Result<Value, Exception> {
+        self.evaluate_block(&mut block).inspect_err(|_| self.recover())
     }
 
     pub fn evaluate_scope(&mut self, block: Block) -> Result<Value, Exception> {
@@ -117,66 +234,536 @@ impl Interpreter {
         // Running a block, so create a scope for it
         self.enter_scope(context);
 
+        let result = self.evaluate_block(&mut block);
+
+        // Done with the block, leave scope, even if it threw
+        self.leave_scope();
+
+        result
+    }
+
+    /// Runs the body of a function with its arguments in `context`. The scope of the body is
+    /// nested in `environment`, the one the function was created in, rather than the caller's.
+    pub fn run_function(
+        &mut self,
+        mut block: Block,
+        context: HashMap<String, Value>,
+        environment: Option<Scope>,
+    ) -> Result<Value, Exception> {
+        let parent = environment.unwrap_or_else(|| Rc::clone(&self.global_environment));
+        let caller = std::mem::replace(
+            &mut self.environment,
+            Environment::new(context, Some(parent)),
+        );
+
+        let result = self.evaluate_block(&mut block);
+
+        // Back to where the function was called from, even if it threw
+        self.environment = caller;
+
+        result
+    }
+
+    /// Runs `run` in a scope of its own, holding `context`, which is left even if it throws.
+    pub fn run_in_scope<T>(
+        &mut self,
+        context: HashMap<String, Value>,
+        run: impl FnOnce(&mut Self) -> Result<T, Exception>,
+    ) -> Result<T, Exception> {
+        self.enter_scope(context);
+        let result = run(self);
+        self.leave_scope();
+        result
+    }
+
+    /// Runs `join` to turn `array` into a string, unless the array is being joined already, in
+    /// which case it contains itself and is joined as an empty string this time.
+    pub fn join_array(
+        &mut self,
+        array: &Handle,
+        join: impl FnOnce(&mut Self) -> Result<String, Exception>,
+    ) -> Result<String, Exception> {
+        if self
+            .joining
+            .iter()
+            .any(|joining| Rc::ptr_eq(joining, array))
+        {
+            return Ok(String::new());
+        }
+        self.joining.push(Rc::clone(array));
+        let result = join(self);
+        self.joining.pop();
+        result
+    }
+
+    fn evaluate_block(&mut self, block: &mut Block) -> Result<Value, Exception> {
         // Every node in the AST may produce a value if it is Expression
         let mut last_value = Value::Undefined;
+        let mut returned = false;
+        let at_top_level = block.get_type() == BlockType::Global;
+        let nested = block.get_type() == BlockType::Control;
+
+        // Bind what the block declares before any of it runs, top level vars are properties of
+        // the global object
+        for name in block.var_names.iter() {
+            match at_top_level {
+                true => self.hoist_global_var(name),
+                false => self.environment.borrow_mut().hoist_var(name),
+            }
+        }
+        let mut environment = self.environment.borrow_mut();
+        for name in block.lexical_names.iter() {
+            environment.hoist_lexical(name);
+        }
+        drop(environment);
+        // Functions declared at the top level are properties of the global object
+        for function in block.functions.iter_mut() {
+            let value = function.evaluate(self)?;
+            let name = function.name().unwrap_or_default();
+            if at_top_level {
+                self.set_global_property(name, value);
+            } else {
+                if nested {
+                    self.write_var(&self.environment(), name, value.clone());
+                }
+                self.environment
+                    .borrow_mut()
+                    .declare(name.to_owned(), value);
+            }
+        }
 
         // Evaluate all of the children of this node
         for node in block.children.iter_mut() {
             last_value = node.evaluate(self)?;
 
             /*
-            Break out of evaluating block, but don't clear, since we are probably
-            running inside Loop::evaluate() and it needs to stop looping. Two rust breaks
-            are needed to get one JS break, one to stop evaluating the block, another to stop
-            iterating.
+            Stop evaluating the block, but leave the completion for whatever encloses it, a loop
+            or label consumes a break or continue meant for it. A return keeps breaking out of
+            blocks until it gets to the body of the function it returns from, which is where the
+            returned value is propagated.
             */
-            if self.break_flag {
+            if self.is_abrupt() {
+                if block.get_type() == BlockType::Function {
+                    if let Completion::Return(value) = self.take_completion() {
+                        last_value = value;
+                        returned = true;
+                    }
+                }
                 break;
             }
 
-            /*
-            Keep breaking out of scopes, which will put us right after the call to evaluate
-            above, until we get to a function, meaning we found the function from which we
-            should return. Then clear return flag, and propagate value.
-            */
-            if self.return_flag {
-                if block.get_type() == BlockType::Function {
-                    self.clear_return();
-                    last_value = self.return_register.take().unwrap_or_default();
+            // Between top level statements, the only object not reachable from the scope stack
+            // is the completion value, making it a safe point to collect
+            if at_top_level && self.heap.should_collect() {
+                self.collect_garbage_with(iter::once(&last_value));
+            }
+        }
+
+        // A function that doesn't return anything returns undefined, not its last statement
+        if block.get_type() == BlockType::Function && !returned {
+            last_value = Value::Undefined;
+        }
+        Ok(last_value)
+    }
+
+    /// Puts the interpreter back into a state where it can keep running after an uncaught
+    /// exception, dropping everything but the global scope.
+    fn recover(&mut self) {
+        self.environment = Rc::clone(&self.global_environment);
+        self.completion = Completion::Normal;
+    }
+
+    /// Calls `function`, which has to be a function object, with `this` bound to `this`.
+    pub fn call_function(
+        &mut self,
+        function: &Handle,
+        this: Value,
+        arguments: Vec<Value>,
+    ) -> Result<Value, Exception> {
+        check_not_class_constructor(function)?;
+        self.call_constructor(function, this, arguments)
+    }
+
+    /// Same as [`Interpreter::call_function`], for `new` and `super` calls, which are the only
+    /// ones that can run the constructor of a class.
+    pub fn call_constructor(
+        &mut self,
+        function: &Handle,
+        this: Value,
+        arguments: Vec<Value>,
+    ) -> Result<Value, Exception> {
+        // Take what is needed to run and drop the borrow, the function may call itself
+        let mut function_borrow = function.borrow_mut();
+        let function = function_borrow.as_function();
+        let name = function.name.clone();
+        let parameters = function.parameters.clone();
+        let block = function.body.clone();
+        let environment = function.environment.clone();
+        let is_built_in = function.is_built_in();
+        let is_arrow = function.is_arrow();
+        drop(function_borrow);
+
+        if is_built_in {
+            return self.handle_built_in(&name.unwrap(), this, arguments);
+        }
+
+        // bind formal parameters to actual parameters (thanks Klefstad)
+        let mut context = HashMap::new();
+        if !is_arrow {
+            context.insert("this".to_owned(), this);
+            let arguments_array = self.new_array(arguments.clone());
+            context.insert("arguments".to_owned(), arguments_array);
+        }
+
+        let parameters_and_arguments = parameters.into_iter().zip_longest(arguments);
+
+        for either_or_both in parameters_and_arguments {
+            match either_or_both {
+                // There is an argument for this parameter, place it in context for the function
+                EitherOrBoth::Both(formal, actual) => {
+                    context.insert(formal, actual);
                 }
-                break;
+                // There is no argument for this parameter, substitute undefined
+                EitherOrBoth::Left(formal) => {
+                    context.insert(formal, Value::Undefined);
+                }
+                // There is an argument but no more parameters, do nothing per spec
+                EitherOrBoth::Right(_) => (),
             }
         }
 
-        // Done with the block, leave scope
-        self.leave_scope();
+        self.run_function(block, context, environment)
+    }
 
-        Ok(last_value)
+    /// Creates the object `new` passes to `constructor` as `this`, inheriting from the
+    /// constructor's `prototype` property. Only functions written in JavaScript can be
+    /// constructors, and of those not arrow functions.
+    pub fn new_instance(&mut self, constructor: &Value) -> Result<Value, Exception> {
+        let prototype = match constructor {
+            Value::Object(o) if o.borrow().get_type() == ObjectType::Function => {
+                let mut object = o.borrow_mut();
+                let function = object.as_function();
+                if function.is_built_in() || function.is_arrow() {
+                    bail!(TypeError("Value is not a constructor".to_owned()));
+                }
+                object.lookup("prototype")
+            }
+            _ => bail!(TypeError("Value is not a constructor".to_owned())),
+        };
+
+        // A prototype that was replaced by something other than an object is ignored
+        let prototype = match prototype {
+            Some(Value::Object(prototype)) => prototype,
+            _ => Rc::clone(&self.object_prototype),
+        };
+        Ok(self.new_object_with(Properties::new(), Some(prototype)))
     }
 
+    /// Runs the built-in function called `name`. Built-ins get all of their arguments, however
+    /// many parameters they declare, along with the object they were called on, if any.
     pub fn handle_built_in(
         &mut self,
         name: &str,
-        context: HashMap<String, Value>,
+        this: Value,
+        arguments: Vec<Value>,
     ) -> Result<Value, Exception> {
         match name {
             "console_log" => {
-                let expr = context
-                    .get("expr")
-                    .ok_or_else(|| ReferenceError("expression".to_owned()))?;
-                println!("{}", expr);
+                let output: Vec<String> = arguments.iter().map(Value::to_string).collect();
+                println!("{}", output.join(" "));
                 success!()
             }
+            "isNaN" => {
+                let value = arguments.into_iter().next().unwrap_or_default();
+                let number = conversions::to_number(self, value)?;
+                success!(Value::Boolean(number.is_nan()))
+            }
+            _ if name.starts_with("array_") => {
+                array::call_method(self, &name["array_".len()..], this, arguments)
+            }
+            _ if name.starts_with("object_") => {
+                object::call_method(self, &name["object_".len()..], this, arguments)
+            }
+            _ if name.starts_with("iterator_") => {
+                iterator::call_method(self, &name["iterator_".len()..], this, arguments)
+            }
+            _ if primitive::TYPES.contains(&name.split('_').next().unwrap_or_default()) => {
+                let (type_name, method) = name.split_once('_').unwrap_or_default();
+                primitive::call_method(self, type_name, method, this)
+            }
             _ => success!(),
         }
     }
 
+    /// Creates an object holding `properties`, inheriting from `Object.prototype`.
+    pub fn new_object(&mut self, properties: Properties) -> Value {
+        let prototype = Rc::clone(&self.object_prototype);
+        self.new_object_with(properties, Some(prototype))
+    }
+
+    /// Creates an object holding `properties`, inheriting from `prototype`.
+    pub fn new_object_with(&mut self, properties: Properties, prototype: Option<Handle>) -> Value {
+        Value::Object(self.allocate(LiteralObject::with_prototype(properties, prototype)))
+    }
+
+    /// Moves `function` onto the heap, inheriting from `Function.prototype`. Functions written in
+    /// JavaScript can be constructors, so they get a `prototype` object for their instances,
+    /// which links back to them as its `constructor`.
+    pub fn new_function(&mut self, function: Box<Function>) -> Value {
+        let is_built_in = function.is_built_in();
+        let function = match self.new_method(function) {
+            Value::Object(function) => function,
+            _ => unreachable!(),
+        };
+
+        if !is_built_in {
+            let mut properties = Properties::new();
+            properties.insert(
+                "constructor".to_owned(),
+                Value::Object(Rc::clone(&function)),
+            );
+            let prototype = self.new_object(properties);
+            if let Value::Object(o) = &prototype {
+                o.borrow_mut().hide("constructor");
+            }
+            function.borrow_mut().put_hidden("prototype", prototype);
+        }
+        Value::Object(function)
+    }
+
+    /// Moves `function` onto the heap, inheriting from `Function.prototype`, without the
+    /// `prototype` object of a constructor. Methods of classes are created this way.
+    pub fn new_method(&mut self, mut function: Box<Function>) -> Value {
+        function.set_prototype(Some(Rc::clone(&self.function_prototype)));
+        Value::Object(self.allocate(function))
+    }
+
+    /// Creates an array holding `elements`.
+    pub fn new_array(&mut self, elements: Vec<Value>) -> Value {
+        let prototype = Rc::clone(&self.array_prototype);
+        Value::Object(self.allocate(ArrayObject::boxed(elements, Some(prototype))))
+    }
+
+    /// Creates an iterator over the elements of `array`.
+    pub fn new_array_iterator(&mut self, array: Value) -> Value {
+        let prototype = Rc::clone(&self.array_iterator_prototype);
+        Value::Object(self.allocate(ArrayIterator::boxed(array, Some(prototype))))
+    }
+
+    /// Creates the object the `next` method of an iterator returns.
+    pub fn new_iterator_result(&mut self, value: Value, done: bool) -> Value {
+        let mut properties = Properties::new();
+        properties.insert("value".to_owned(), value);
+        properties.insert("done".to_owned(), Value::Boolean(done));
+        self.new_object(properties)
+    }
+
+    /// Finds the method that gets an iterator for `iterable`, returning it along with the value
+    /// to call it on. Strings are iterable too, over their characters.
+    pub fn iterator_method(&mut self, iterable: Value) -> Result<(Value, Handle), Exception> {
+        let iterable = match iterable {
+            Value::StringLiteral(s) => {
+                let characters = s
+                    .chars()
+                    .map(|c| Value::StringLiteral(c.to_string()))
+                    .collect();
+                self.new_array(characters)
+            }
+            _ => iterable,
+        };
+        let method = match &iterable {
+            Value::Object(o) => o.borrow().lookup(iterator::ITERATOR_KEY),
+            _ => None,
+        };
+        match method {
+            Some(Value::Object(method)) if method.borrow().get_type() == ObjectType::Function => {
+                Ok((iterable, method))
+            }
+            _ => Err(TypeError(format!("{} is not iterable", iterable))),
+        }
+    }
+
+    /// Gets an iterator for `iterable` by calling its iterator method.
+    pub fn get_iterator(&mut self, iterable: Value) -> Result<Handle, Exception> {
+        let (iterable, method) = self.iterator_method(iterable)?;
+        match self.call_function(&method, iterable, Vec::new())? {
+            Value::Object(iterator) => Ok(iterator),
+            _ => Err(TypeError(
+                "Result of the Symbol.iterator method is not an object".to_owned(),
+            )),
+        }
+    }
+
+    /// Calls the `next` method of `iterator`, returning the value it produced, or nothing once
+    /// it is done.
+    pub fn iterator_step(&mut self, iterator: &Handle) -> Result<Option<Value>, Exception> {
+        let next = iterator.borrow().lookup("next");
+        let result = match next {
+            Some(Value::Object(next)) if next.borrow().get_type() == ObjectType::Function => {
+                let receiver = Value::Object(Rc::clone(iterator));
+                self.call_function(&next, receiver, Vec::new())?
+            }
+            _ => bail!(TypeError("Iterator has no next method".to_owned())),
+        };
+        iterator::result_value(&result)
+    }
+
+    /// Reads the property `key` of `object`, which may be inherited, and is undefined if it
+    /// doesn't exist. The getter of an accessor property is called with `receiver` as `this`.
+    pub fn get_property(
+        &mut self,
+        object: &Handle,
+        key: &str,
+        receiver: Value,
+    ) -> Result<Value, Exception> {
+        let value = object.borrow().lookup(key).unwrap_or_default();
+        self.property_value(value, receiver)
+    }
+
+    /// Same as [`Interpreter::get_property`], for a property of any value, which is the receiver.
+    pub fn get_value_property(&mut self, value: &Value, key: &str) -> Result<Value, Exception> {
+        let property = self.lookup_property(value, key)?;
+        self.property_value(property, value.clone())
+    }
+
+    /// Looks up the property `key` of any value, which is undefined if it doesn't exist.
+    /// Primitives have the properties of the prototype of their type, undefined and null have
+    /// none at all.
+    pub fn lookup_property(&self, value: &Value, key: &str) -> Result<Value, Exception> {
+        let prototype = match value {
+            Value::Object(o) => return Ok(o.borrow().lookup(key).unwrap_or_default()),
+            Value::Undefined | Value::Null => bail!(TypeError("Value is not an object".to_owned())),
+            Value::StringLiteral(s) => match primitive::string_property(s, key) {
+                Some(property) => return Ok(property),
+                None => &self.string_prototype,
+            },
+            Value::Number(_) => &self.number_prototype,
+            Value::Boolean(_) => &self.boolean_prototype,
+        };
+        Ok(prototype.borrow().lookup(key).unwrap_or_default())
+    }
+
+    // The value read from a property, calling its getter on `receiver` if it is an accessor
+    fn property_value(&mut self, value: Value, receiver: Value) -> Result<Value, Exception> {
+        match Accessor::functions(&value) {
+            None => Ok(value),
+            Some((Some(getter), _)) => self.call_function(&getter, receiver, Vec::new()),
+            Some((None, _)) => success!(),
+        }
+    }
+
+    /// Assigns to the property `key` of `object`. If the property is an accessor, its setter is
+    /// called instead, even if it is inherited.
+    pub fn set_property(
+        &mut self,
+        object: &Handle,
+        key: &str,
+        value: Value,
+    ) -> Result<(), Exception> {
+        let existing = object.borrow().lookup(key);
+        match existing.as_ref().and_then(Accessor::functions) {
+            None => {
+                let value = array::assigned_value(self, object, key, value)?;
+                object.borrow_mut().set(key, value)
+            }
+            Some((_, Some(setter))) => {
+                self.call_function(&setter, Value::Object(Rc::clone(object)), vec![value])?;
+                Ok(())
+            }
+            Some((_, None)) => Err(TypeError(format!(
+                "Cannot set property {} which has only a getter",
+                key
+            ))),
+        }
+    }
+
+    /// Deletes the property `key` of `object`, returning whether it is gone. Primitives have no
+    /// properties of their own to delete.
+    pub fn delete_property(&mut self, object: &Value, key: &str) -> Result<bool, Exception> {
+        match object {
+            Value::Object(o) => Ok(o.borrow_mut().delete(key)),
+            Value::Undefined | Value::Null => Err(TypeError(format!(
+                "Cannot delete property {} of {}",
+                key,
+                object.property_key()
+            ))),
+            _ => Ok(true),
+        }
+    }
+
     pub fn at_global(&self) -> bool {
-        self.scope_stack.len() == 1
+        Rc::ptr_eq(&self.environment, &self.global_environment)
     }
 
     pub fn add_variable(&mut self, key: String, value: Value) {
-        self.scope_stack.last_mut().unwrap().insert(key, value);
+        self.environment.borrow_mut().declare(key, value);
+    }
+
+    /// Declares a variable in the innermost scope that can't be assigned to.
+    pub fn add_constant(&mut self, key: String, value: Value) {
+        self.environment.borrow_mut().declare_constant(key, value);
+    }
+
+    /// The innermost scope of the code being run, which functions created now capture.
+    pub fn environment(&self) -> Scope {
+        Rc::clone(&self.environment)
+    }
+
+    pub fn global_environment(&self) -> Scope {
+        Rc::clone(&self.global_environment)
+    }
+
+    pub fn global_object(&self) -> Handle {
+        Rc::clone(&self.global_object)
+    }
+
+    /// The prototype plain objects are created with.
+    pub fn object_prototype(&self) -> Handle {
+        Rc::clone(&self.object_prototype)
+    }
+
+    pub fn heap(&self) -> &Heap {
+        &self.heap
+    }
+
+    /// Moves `object` onto the heap, so the collector can free it if it ends up in a cycle.
+    pub fn allocate(&mut self, object: Box<dyn Object>) -> Handle {
+        self.heap.allocate(object)
+    }
+
+    /// Frees every object that isn't reachable from the interpreter's scopes, global object or
+    /// return register, returning how many were freed.
+    ///
+    /// Values held only by the caller aren't roots, so this is only safe to call when the
+    /// interpreter isn't running, e.g. between calls to [`Interpreter::run`].
+    pub fn collect_garbage(&mut self) -> usize {
+        self.collect_garbage_with(iter::empty())
+    }
+
+    /// Same as [`Interpreter::collect_garbage`], keeping everything reachable from `roots` too.
+    pub fn collect_garbage_with<'a>(
+        &mut self,
+        roots: impl IntoIterator<Item = &'a Value>,
+    ) -> usize {
+        let mut all_roots = vec![
+            Value::Object(self.global_object()),
+            Value::Object(Rc::clone(&self.object_prototype)),
+            Value::Object(Rc::clone(&self.function_prototype)),
+            Value::Object(Rc::clone(&self.array_prototype)),
+            Value::Object(Rc::clone(&self.array_iterator_prototype)),
+            Value::Object(Rc::clone(&self.string_prototype)),
+            Value::Object(Rc::clone(&self.number_prototype)),
+            Value::Object(Rc::clone(&self.boolean_prototype)),
+        ];
+        let mut visit = |value: &Value| all_roots.push(value.clone());
+        self.environment.borrow().trace(&mut visit);
+        self.global_environment.borrow().trace(&mut visit);
+        if let Completion::Return(value) = &self.completion {
+            all_roots.push(value.clone());
+        }
+        all_roots.extend(roots.into_iter().cloned());
+
+        self.heap.collect(all_roots.iter())
     }
 
     pub fn global_property(&self, name: &str) -> Result<Value, Exception> {
@@ -191,32 +778,47 @@ impl Interpreter {
         self.global_object.borrow_mut().put(name, property)
     }
 
+    /// Declares the top level `var` called `name` as a property of the global object, which is
+    /// undefined unless the global object has it already.
+    pub fn hoist_global_var(&mut self, name: &str) {
+        let mut global_object = self.global_object.borrow_mut();
+        if global_object.get(name).is_none() {
+            global_object.put(name, Value::Undefined);
+        }
+    }
+
+    /// Same as [`Environment::write_var`], for a `var` that may be declared at the top level.
+    pub fn write_var(&mut self, scope: &Scope, name: &str, value: Value) {
+        if !Environment::write_var(scope, name, value.clone()) {
+            if let Some(property) = self.global_object.borrow_mut().get_mut(name) {
+                *property = value;
+            }
+        }
+    }
+
+    /// Same as [`Interpreter::variable`], but a variable that isn't declared is undefined.
+    pub fn variable_if_declared(&mut self, name: &str) -> Result<Value, Exception> {
+        undeclared_as_undefined(name, self.variable(name))
+    }
+
     /// Get the value of a variable with name `name`, using scope resolution.
     pub fn variable(&mut self, name: &str) -> Result<Value, Exception> {
-        let x = self.global_object.borrow();
-        match self.resolve_variable(name).cloned() {
-            None => match x.get(name).cloned() {
-                None => Err(ReferenceError(name.to_owned())),
-                Some(v) => Ok(v),
-            },
+        match Environment::read(&self.environment, name)? {
+            None => self.global_property(name),
             Some(v) => Ok(v),
         }
     }
 
-    /// Finds the variable with the given `name`, and applies the closure `edit` to it.
+    /// Finds the variable with the given `name`, and applies the closure `edit` to it. The
+    /// edited value is assigned back, so constants can't be edited.
     pub fn edit_variable<F>(&mut self, name: &str, edit: F) -> Result<Value, Exception>
     where
         F: FnOnce(&mut Value) -> Result<Value, Exception>,
     {
-        // Look up in normal scope stack
-        match self.resolve_variable_mut(name) {
-            // If not found, check if it is a property of the global object
-            None => match self.global_object.borrow_mut().get_mut(name) {
-                None => Err(ReferenceError(name.to_owned())),
-                Some(v) => edit(v),
-            },
-            Some(v) => edit(v),
-        }
+        let mut value = self.variable(name)?;
+        let result = edit(&mut value)?;
+        self.assign_variable(name, value)?;
+        Ok(result)
     }
 
     ///
@@ -245,85 +847,173 @@ impl Interpreter {
     where
         F: FnOnce(&Value) -> Result<Value, Exception>,
     {
-        // Look up in normal scope stack
-        match self.resolve_variable(name) {
-            // If not found, check if it is a property of the global object
-            None => match self.global_object.borrow_mut().get(name) {
-                None => Err(ReferenceError(name.to_owned())),
-                Some(v) => inspect(v),
-            },
-            Some(v) => inspect(v),
-        }
+        let value = self.variable(name)?;
+        inspect(&value)
     }
 
+    /// Assigns `value` to the variable `name`, using scope resolution. Constants can't be
+    /// assigned to, and neither can bindings whose declaration hasn't run yet.
     pub fn assign_variable(&mut self, name: &str, value: Value) -> Result<Value, Exception> {
-        self.edit_variable(name, |lvalue: &mut Value| -> Result<Value, Exception> {
-            *lvalue = value.clone();
-            Ok(value)
-        })
-    }
+        if Environment::write(&self.environment, name, value.clone())? {
+            return Ok(value);
+        }
 
-    pub fn notify_break(&mut self) {
-        self.break_flag = true;
-    }
-    pub fn clear_break(&mut self) {
-        self.break_flag = false;
-    }
-    pub fn broke(&self) -> bool {
-        self.break_flag
+        // If not found, check if it is a property of the global object
+        match self.global_object.borrow_mut().get_mut(name) {
+            None => Err(ReferenceError(name.to_owned())),
+            Some(v) => {
+                *v = value.clone();
+                Ok(value)
+            }
+        }
     }
 
-    pub fn notify_return(&mut self) {
-        self.return_flag = true;
-    }
-    pub fn clear_return(&mut self) {
-        self.return_flag = false;
-    }
-    pub fn returned(&self) -> bool {
-        self.return_flag
+    /// Records how the current statement finished, for the enclosing statements to act on.
+    pub fn complete(&mut self, completion: Completion) {
+        self.completion = completion;
     }
 
-    pub fn suppress_declarations(&mut self) {
-        self.declaration_suppression_counter += 1
-    }
-    pub fn allow_declarations(&mut self) {
-        self.declaration_suppression_counter -= 1;
+    /// Whether the current statement finished with a break, continue or return.
+    pub fn is_abrupt(&self) -> bool {
+        self.completion != Completion::Normal
     }
-    pub fn should_suppress_declarations(&self) -> bool {
-        self.declaration_suppression_counter > 0
+
+    /// Clears the completion of the current statement, so that other code can run before it
+    /// continues with [`Interpreter::complete`].
+    pub fn take_completion(&mut self) -> Completion {
+        std::mem::replace(&mut self.completion, Completion::Normal)
     }
 
-    pub fn set_return_val(&mut self, val: Value) {
-        self.return_register = Some(val)
+    /// Checks the completion after the body of a loop ran, returning whether the loop should
+    /// stop. A break or continue meant for the loop, being unlabeled or naming one of its
+    /// `labels`, is consumed. Anything else stops the loop and keeps unwinding.
+    pub fn should_exit_loop(&mut self, labels: &[String]) -> bool {
+        let targets_loop = |label: &Option<String>| match label {
+            None => true,
+            Some(label) => labels.contains(label),
+        };
+
+        match &self.completion {
+            Completion::Normal => false,
+            Completion::Continue(label) if targets_loop(label) => {
+                self.completion = Completion::Normal;
+                false
+            }
+            Completion::Break(label) if targets_loop(label) => {
+                self.completion = Completion::Normal;
+                true
+            }
+            _ => true,
+        }
     }
 
     fn enter_scope(&mut self, scope: HashMap<String, Value>) {
-        self.scope_stack.push(scope);
+        let parent = Rc::clone(&self.environment);
+        self.environment = Environment::new(scope, Some(parent));
     }
 
-    fn leave_scope(&mut self) {
-        assert!(self.scope_stack.last().is_some());
-        self.scope_stack.pop();
+    /// Replaces the innermost scope with a copy of it, see [`Environment::copy`].
+    pub fn copy_scope(&mut self) {
+        self.environment = Environment::copy(&self.environment);
     }
 
-    fn resolve_variable_mut(&mut self, name: &str) -> Option<&mut Value> {
-        self.scope_stack
-            .iter_mut()
-            .rev()
-            .find_map(|scope| scope.get_mut(name))
+    fn leave_scope(&mut self) {
+        let parent = self.environment.borrow().parent();
+        self.environment = parent.expect("Cannot leave the global scope");
     }
 
-    fn resolve_variable(&self, name: &str) -> Option<&Value> {
-        self.scope_stack
-            .iter()
-            .rev()
-            .find_map(|scope| scope.get(name))
+    fn populate_built_ins(&mut self) {
+        for method in object::PROTOTYPE_METHODS {
+            let function = self.built_in(&format!("object_{}", method));
+            self.object_prototype
+                .borrow_mut()
+                .put_hidden(method, function);
+        }
+        for method in array::METHODS {
+            let function = self.built_in(&format!("array_{}", method));
+            self.array_prototype
+                .borrow_mut()
+                .put_hidden(method, function);
+        }
+        for method in iterator::METHODS {
+            let function = self.built_in(&format!("iterator_{}", method));
+            let prototype = &self.array_iterator_prototype;
+            prototype.borrow_mut().put_hidden(method, function);
+        }
+        let primitive_prototypes = [
+            &self.string_prototype,
+            &self.number_prototype,
+            &self.boolean_prototype,
+        ]
+        .map(Rc::clone);
+        for (type_name, prototype) in primitive::TYPES.iter().zip(primitive_prototypes) {
+            for method in primitive::METHODS {
+                let function = self.built_in(&format!("{}_{}", type_name, method));
+                prototype.borrow_mut().put_hidden(method, function);
+            }
+        }
+        // Arrays are iterable, iterating over their values
+        let values = self.array_prototype.borrow().get("values").cloned();
+        self.array_prototype
+            .borrow_mut()
+            .put_hidden(iterator::ITERATOR_KEY, values.unwrap_or_default());
+
+        let mut object = HashMap::new();
+        for method in object::STATIC_METHODS {
+            object.insert(
+                method.to_owned(),
+                self.built_in(&format!("object_{}", method)),
+            );
+        }
+
+        // The constructors are built-in functions, so that `instanceof` works with them
+        let constructors = [
+            ("Object", object, Rc::clone(&self.object_prototype)),
+            (
+                "Function",
+                HashMap::new(),
+                Rc::clone(&self.function_prototype),
+            ),
+            ("Array", HashMap::new(), Rc::clone(&self.array_prototype)),
+        ];
+        for (name, properties, prototype) in constructors {
+            let constructor = self.built_in(name);
+            if let Value::Object(o) = &constructor {
+                let mut o = o.borrow_mut();
+                for (key, value) in properties {
+                    o.put_hidden(&key, value);
+                }
+                o.put_hidden("prototype", Value::Object(Rc::clone(&prototype)));
+            }
+            prototype
+                .borrow_mut()
+                .put_hidden("constructor", constructor.clone());
+            self.set_global_property(name, constructor);
+        }
+
+        // There are no symbols, the well-known ones are stand-in strings
+        let mut symbol = Properties::new();
+        symbol.insert(
+            "iterator".to_owned(),
+            Value::StringLiteral(iterator::ITERATOR_KEY.to_owned()),
+        );
+        let symbol = self.new_object(symbol);
+        self.set_global_property("Symbol", symbol);
+
+        let mut console = Console::new();
+        console.set_prototype(Some(Rc::clone(&self.object_prototype)));
+        let console = Value::Object(self.allocate(Box::new(console)));
+        self.set_global_property("console", console);
+        self.set_global_property("undefined", Value::Undefined);
+        self.set_global_property("NaN", Value::Number(f64::NAN));
+        self.set_global_property("Infinity", Value::Number(f64::INFINITY));
+        let is_nan = self.built_in("isNaN");
+        self.set_global_property("isNaN", is_nan);
     }
 
-    fn populate_built_ins(global_object: Rc<RefCell<Box<dyn Object>>>) {
-        global_object
-            .borrow_mut()
-            .put("console", Console::new().value());
+    /// Creates the function object for the built-in called `name`.
+    fn built_in(&mut self, name: &str) -> Value {
+        self.new_function(Function::built_in(Some(name.to_owned()), Vec::new()))
     }
 
     fn handle_exception(&mut self, _exception: Exception) {
@@ -331,3 +1021,28 @@ impl Interpreter {
         eprintln!("{}", _exception);
     }
 }
+
+/// Fails if `function` is the constructor of a class, which only `new` and `super` can call.
+pub fn check_not_class_constructor(function: &Handle) -> Result<(), Exception> {
+    let mut function = function.borrow_mut();
+    let function = function.as_function();
+    if function.is_class_constructor() {
+        bail!(TypeError(format!(
+            "Class constructor {} cannot be invoked without 'new'",
+            function.name.as_deref().unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+/// Turns the error of looking up the undeclared variable `name` into undefined, which is what
+/// `typeof` evaluates its operand to.
+pub fn undeclared_as_undefined(
+    name: &str,
+    lookup: Result<Value, Exception>,
+) -> Result<Value, Exception> {
+    match lookup {
+        Err(ReferenceError(missing)) if missing == name => success!(),
+        other => other,
+    }
+}
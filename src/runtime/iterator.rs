@@ -0,0 +1,131 @@
+//! The iterator protocol for-of loops drive. Without symbols, the method that makes an object
+//! iterable is the property named [`ITERATOR_KEY`], which is what `Symbol.iterator` evaluates to.
+use std::{collections::HashSet, fmt, rc::Rc};
+
+use crate::runtime::{
+    conversions, exception::*, object, Handle, Interpreter, Keys, Object, ObjectType, Properties,
+    Prototype, Trace, Value,
+};
+
+/// The key of the method returning an iterator, standing in for `Symbol.iterator`.
+pub const ITERATOR_KEY: &str = "@@iterator";
+
+/// The methods of the prototype of array iterators, each one is a built-in function named
+/// `iterator_<method>`.
+pub const METHODS: [&str; 1] = ["next"];
+
+/// An iterator over the elements of an array, which keeps the array and its position out of
+/// reach of scripts.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Object, Clone)]
+#[object_type(ArrayIterator)]
+pub struct ArrayIterator {
+    properties: Properties,
+    prototype: Option<Handle>,
+    array: Value,
+    // The index of the next element
+    index: usize,
+}
+
+impl ArrayIterator {
+    pub fn boxed(array: Value, prototype: Option<Handle>) -> Box<Self> {
+        Box::new(Self {
+            properties: Properties::new(),
+            prototype,
+            array,
+            index: 0,
+        })
+    }
+}
+
+impl fmt::Display for ArrayIterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_properties())
+    }
+}
+
+impl Keys for ArrayIterator {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &HashSet::new())
+    }
+}
+
+impl Prototype for ArrayIterator {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for ArrayIterator {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        visit(&self.array);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+        self.array = Value::Undefined;
+    }
+}
+
+/// Runs the method of array iterators called `method` on `this`.
+pub fn call_method(
+    interpreter: &mut Interpreter,
+    method: &str,
+    this: Value,
+    _arguments: Vec<Value>,
+) -> Result<Value, Exception> {
+    let iterator = match (&this, method) {
+        (Value::Object(o), "next") if o.borrow().get_type() == ObjectType::ArrayIterator => {
+            Rc::clone(o)
+        }
+        (_, "next") => bail!(TypeError(
+            "next method called on incompatible receiver".to_owned()
+        )),
+        _ => bail!(TypeError(format!("Iterator.{} is not a function", method))),
+    };
+
+    let (array, index) = {
+        let mut iterator = iterator.borrow_mut();
+        let iterator = iterator.as_array_iterator();
+        (iterator.array.clone(), iterator.index)
+    };
+
+    // The length is checked on every step, elements pushed while iterating are visited too
+    let element = match &array {
+        Value::Object(o) if o.borrow().get_type() == ObjectType::Array => {
+            let mut array = o.borrow_mut();
+            array.as_array().element(index)
+        }
+        _ => None,
+    };
+
+    let done = element.is_none();
+    if !done {
+        iterator.borrow_mut().as_array_iterator().index = index + 1;
+    }
+    Ok(interpreter.new_iterator_result(element.unwrap_or_default(), done))
+}
+
+/// The value of the result of calling `next` on an iterator, or nothing if it says it is done.
+pub fn result_value(result: &Value) -> Result<Option<Value>, Exception> {
+    let result = match result {
+        Value::Object(o) => o.borrow(),
+        _ => bail!(TypeError(format!(
+            "Iterator result {} is not an object",
+            result
+        ))),
+    };
+    match conversions::to_boolean(&result.lookup("done").unwrap_or_default()) {
+        true => Ok(None),
+        false => Ok(Some(result.lookup("value").unwrap_or_default())),
+    }
+}
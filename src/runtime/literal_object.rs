@@ -1,6 +1,6 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashSet, fmt, rc::Rc};
 
-use crate::runtime::{Object, Value};
+use crate::runtime::{object, Handle, Keys, Object, Properties, Prototype, Trace, Value};
 
 // TODO: make a derive macro that can implement object for a type by annotating the
 //  field with a map interface
@@ -8,16 +8,30 @@ use crate::runtime::{Object, Value};
 #[derive(Object, Clone)]
 #[object_type(Object)]
 pub struct LiteralObject {
-    properties: HashMap<String, Value>,
+    properties: Properties,
+    prototype: Option<Handle>,
+    // The keys of the properties that aren't enumerated
+    hidden: HashSet<String>,
 }
 
 impl LiteralObject {
-    pub fn new(properties: HashMap<String, Value>) -> Self {
-        Self {properties}
+    pub fn new(properties: Properties) -> Self {
+        Self {
+            properties,
+            prototype: None,
+            hidden: HashSet::new(),
+        }
     }
-    pub fn boxed(properties: HashMap<String, Value>) -> Box<Self> {
+    pub fn boxed(properties: Properties) -> Box<Self> {
         Box::new(Self::new(properties))
     }
+    pub fn with_prototype(properties: Properties, prototype: Option<Handle>) -> Box<Self> {
+        Box::new(Self {
+            properties,
+            prototype,
+            hidden: HashSet::new(),
+        })
+    }
 }
 
 impl fmt::Display for LiteralObject {
@@ -25,3 +39,43 @@ impl fmt::Display for LiteralObject {
         f.write_str(&self.format_properties())
     }
 }
+
+impl Keys for LiteralObject {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &self.hidden)
+    }
+
+    fn hide(&mut self, key: &str) {
+        self.hidden.insert(key.to_owned());
+    }
+
+    fn delete(&mut self, key: &str) -> bool {
+        self.properties.remove(key);
+        self.hidden.remove(key);
+        true
+    }
+}
+
+impl Prototype for LiteralObject {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for LiteralObject {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+    }
+}
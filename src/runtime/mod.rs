@@ -1,20 +1,35 @@
+pub use accessor::Accessor;
+pub use array::ArrayObject;
 pub use console::Console;
+pub use environment::{Environment, Scope};
 pub use exception::Exception;
 pub use function::Function;
-pub use interpreter::Interpreter;
+pub use heap::{Handle, Heap, HeapStats, Trace};
+pub use interpreter::{Completion, Interpreter};
 pub use literal_object::LiteralObject;
-pub use object::Object;
 pub use object::Type as ObjectType;
+pub use object::{for_in_keys, Keys, Object, Prototype};
+pub use properties::Properties;
 pub use string::JsString;
 pub use value::Value;
+pub use vm::Vm;
 
+mod accessor;
+mod array;
+pub mod bytecode;
+pub mod class;
+pub mod conversions;
 mod console;
+mod environment;
 pub mod exception;
 mod function;
+mod heap;
 mod interpreter;
+mod iterator;
 mod literal_object;
 mod object;
+mod primitive;
+mod properties;
 mod string;
 mod value;
 mod vm;
-mod heap;
@@ -1,6 +1,17 @@
 pub use jsrs_derive::Object;
 
-use crate::runtime::{Function, interpreter::GlobalObject, JsString, Value};
+use std::{collections::HashSet, rc::Rc};
+
+use crate::runtime::{
+    exception::*, interpreter::GlobalObject, iterator::ArrayIterator, primitive, Accessor,
+    ArrayObject, Function, Handle, Interpreter, JsString, Properties, Trace, Value,
+};
+
+/// The functions on the global `Object`, each one is a built-in function named `object_<name>`.
+pub const STATIC_METHODS: [&str; 3] = ["create", "getPrototypeOf", "setPrototypeOf"];
+
+/// The methods of `Object.prototype`, named the same way as [`STATIC_METHODS`].
+pub const PROTOTYPE_METHODS: [&str; 3] = ["hasOwnProperty", "toString", "valueOf"];
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Eq, PartialEq)]
@@ -8,15 +19,18 @@ pub enum Type {
     Object,
     Global,
     Function,
-    // TODO
     Array,
     String,
+    Accessor,
+    ArrayIterator,
 }
 
 // TODO: properties can be much more complicated that always a key:value, or can they?
 //  We can handle nested objects, functions (named or anonymous). Other oddities are just in
 //  shorthand, which may under the hood just be <name, value>.
-pub trait Object: std::fmt::Debug + ObjectClone + std::fmt::Display {
+pub trait Object:
+    std::fmt::Debug + ObjectClone + std::fmt::Display + Trace + Prototype + Keys
+{
     fn put(&mut self, name: &str, value: Value);
 
     // TODO: These two should return exceptions instead of None
@@ -24,6 +38,29 @@ pub trait Object: std::fmt::Debug + ObjectClone + std::fmt::Display {
 
     fn get_mut(&mut self, name: &str) -> Option<&mut Value>;
 
+    /// Looks up a property on the object itself, then along its chain of prototypes.
+    fn lookup(&self, name: &str) -> Option<Value> {
+        match self.get(name) {
+            Some(value) => Some(value.clone()),
+            None => self
+                .prototype()
+                .and_then(|prototype| prototype.borrow().lookup(name)),
+        }
+    }
+
+    /// Assigns to a property of the object itself, creating it if needed. An inherited property
+    /// of the same name is shadowed, not changed.
+    fn set(&mut self, name: &str, value: Value) -> Result<(), Exception> {
+        self.put(name, value);
+        Ok(())
+    }
+
+    /// Puts a property that isn't enumerated, the way built-in methods and `constructor` aren't.
+    fn put_hidden(&mut self, name: &str, value: Value) {
+        self.put(name, value);
+        self.hide(name);
+    }
+
     fn get_type(&self) -> Type;
 
     fn as_any(&mut self) -> &mut dyn std::any::Any;
@@ -35,19 +72,105 @@ pub trait Object: std::fmt::Debug + ObjectClone + std::fmt::Display {
         self.as_any().downcast_mut::<Function>().unwrap()
     }
 
+    fn as_array(&mut self) -> &mut ArrayObject {
+        assert_eq!(self.get_type(), Type::Array);
+        self.as_any().downcast_mut::<ArrayObject>().unwrap()
+    }
+
     fn as_global(&mut self) -> &mut GlobalObject {
         assert_eq!(self.get_type(), Type::Global);
         self.as_any().downcast_mut::<GlobalObject>().unwrap()
     }
 
+    fn as_accessor(&mut self) -> &mut Accessor {
+        assert_eq!(self.get_type(), Type::Accessor);
+        self.as_any().downcast_mut::<Accessor>().unwrap()
+    }
+
     fn as_string(&mut self) -> &mut JsString {
         assert_eq!(self.get_type(), Type::String);
         self.as_any().downcast_mut::<JsString>().unwrap()
     }
 
+    fn as_array_iterator(&mut self) -> &mut ArrayIterator {
+        assert_eq!(self.get_type(), Type::ArrayIterator);
+        self.as_any().downcast_mut::<ArrayIterator>().unwrap()
+    }
+
     fn format_properties(&self) -> String;
 }
 
+/// The link from an object to the one it inherits properties from.
+pub trait Prototype {
+    fn prototype(&self) -> Option<Handle>;
+
+    fn set_prototype(&mut self, prototype: Option<Handle>);
+}
+
+/// The properties of an object that are enumerated, by for-in loops among others, and deleted.
+pub trait Keys {
+    /// The keys of the object's own enumerable properties, in the order they are enumerated.
+    fn own_keys(&self) -> Vec<String>;
+
+    /// Stops the property `key` from being enumerated. Objects that don't keep track of that
+    /// enumerate every property.
+    fn hide(&mut self, _key: &str) {}
+
+    /// Deletes the own property `key`, returning whether the object doesn't have it anymore.
+    /// Objects that don't support deleting keep all of their properties.
+    fn delete(&mut self, _key: &str) -> bool {
+        false
+    }
+}
+
+/// Orders the keys of `properties` that aren't `hidden` the way they are enumerated: keys that
+/// are array indices come first in ascending order, followed by the rest in the order they were
+/// added.
+pub fn enumerable_keys(properties: &Properties, hidden: &HashSet<String>) -> Vec<String> {
+    let (mut indices, names): (Vec<_>, Vec<_>) = properties
+        .keys()
+        .filter(|key| !hidden.contains(*key))
+        .map(|key| (array_index(key), key.clone()))
+        .partition(|(index, _)| index.is_some());
+    indices.sort_by_key(|(index, _)| *index);
+    indices
+        .into_iter()
+        .chain(names)
+        .map(|(_, key)| key)
+        .collect()
+}
+
+// The index `key` names if it is the canonical form of an array index
+fn array_index(key: &str) -> Option<u32> {
+    key.parse::<u32>()
+        .ok()
+        .filter(|index| *index < u32::MAX && index.to_string() == key)
+}
+
+/// The keys a for-in loop over `value` visits, those of the enumerable properties of an object
+/// and then of its prototypes, skipping any that a property earlier in the chain shadows. Strings
+/// enumerate the indices of their characters, any other primitive nothing.
+pub fn for_in_keys(value: &Value) -> Vec<String> {
+    let object = match value {
+        Value::Object(o) => o,
+        Value::StringLiteral(s) => return (0..s.chars().count()).map(|i| i.to_string()).collect(),
+        _ => return Vec::new(),
+    };
+
+    let mut keys = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = Some(Rc::clone(object));
+    while let Some(link) = current {
+        for key in link.borrow().own_keys() {
+            if seen.insert(key.clone()) {
+                keys.push(key);
+            }
+        }
+        current = link.borrow().prototype();
+    }
+    keys
+}
+
 // Helper trait to allow object-safety
 pub trait ObjectClone {
     fn clone_box(&self) -> Box<dyn Object>;
@@ -67,6 +190,102 @@ impl Clone for Box<dyn Object> {
     }
 }
 
+/// Whether `prototype` is `object`, or inherits from it.
+fn inherits_from(prototype: &Handle, object: &Handle) -> bool {
+    let mut current = Some(Rc::clone(prototype));
+    while let Some(link) = current {
+        if Rc::ptr_eq(&link, object) {
+            return true;
+        }
+        current = link.borrow().prototype();
+    }
+    false
+}
+
+/// The prototype a value stands for in `Object.create` and `Object.setPrototypeOf`, where null
+/// stands for no prototype at all.
+fn as_prototype(value: Option<&Value>) -> Result<Option<Handle>, Exception> {
+    match value {
+        Some(Value::Object(o)) => Ok(Some(Rc::clone(o))),
+        Some(Value::Null) => Ok(None),
+        _ => Err(TypeError(
+            "Object prototype may only be an Object or null".to_owned(),
+        )),
+    }
+}
+
+/// Runs the built-in called `object_<name>`, either a function on the global `Object` or a
+/// method of `Object.prototype` called on `this`.
+pub fn call_method(
+    interpreter: &mut Interpreter,
+    name: &str,
+    this: Value,
+    arguments: Vec<Value>,
+) -> Result<Value, Exception> {
+    let object = |index: usize| match arguments.get(index) {
+        Some(Value::Object(o)) => Ok(Rc::clone(o)),
+        _ => Err(TypeError(format!("Object.{} called on non-object", name))),
+    };
+
+    let value = match name {
+        "create" => {
+            let prototype = as_prototype(arguments.first())?;
+            interpreter.new_object_with(Default::default(), prototype)
+        }
+        "getPrototypeOf" => match object(0)?.borrow().prototype() {
+            Some(prototype) => Value::Object(prototype),
+            None => Value::Null,
+        },
+        "setPrototypeOf" => {
+            let object = object(0)?;
+            let prototype = as_prototype(arguments.get(1))?;
+            if let Some(prototype) = prototype.as_ref() {
+                if inherits_from(prototype, &object) {
+                    bail!(TypeError("Cyclic __proto__ value".to_owned()));
+                }
+            }
+            object.borrow_mut().set_prototype(prototype);
+            Value::Object(object)
+        }
+        "hasOwnProperty" => {
+            let key = arguments
+                .first()
+                .cloned()
+                .unwrap_or_default()
+                .property_key();
+            match this {
+                Value::Object(o) => Value::Boolean(o.borrow().get(&key).is_some()),
+                Value::StringLiteral(s) => {
+                    Value::Boolean(primitive::string_property(&s, &key).is_some())
+                }
+                Value::Number(_) | Value::Boolean(_) => Value::Boolean(false),
+                _ => bail!(TypeError(
+                    "Object.prototype.hasOwnProperty called on non-object".to_owned()
+                )),
+            }
+        }
+        "toString" => {
+            let tag = match &this {
+                Value::Object(o) => match o.borrow().get_type() {
+                    Type::Array => "Array",
+                    Type::Function => "Function",
+                    _ => "Object",
+                },
+                Value::Undefined => "Undefined",
+                Value::Null => "Null",
+                Value::Number(_) => "Number",
+                Value::Boolean(_) => "Boolean",
+                Value::StringLiteral(_) => "String",
+            };
+            Value::StringLiteral(format!("[object {}]", tag))
+        }
+        "valueOf" => this,
+        _ => bail!(TypeError(format!("Object.{} is not a function", name))),
+    };
+
+    Ok(value)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -87,6 +306,30 @@ mod tests {
         }
     }
 
+    impl Keys for SomeObject {
+        fn own_keys(&self) -> Vec<String> {
+            self.properties.keys().cloned().collect()
+        }
+    }
+
+    impl Prototype for SomeObject {
+        fn prototype(&self) -> Option<Handle> {
+            None
+        }
+
+        fn set_prototype(&mut self, _prototype: Option<Handle>) {}
+    }
+
+    impl Trace for SomeObject {
+        fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+            self.properties.values().for_each(visit)
+        }
+
+        fn unlink(&mut self) {
+            self.properties.clear()
+        }
+    }
+
     #[derive(Object, Clone, Debug)]
     #[object_type(Object)]
     struct SomeOtherObject {
@@ -100,6 +343,30 @@ mod tests {
         }
     }
 
+    impl Keys for SomeOtherObject {
+        fn own_keys(&self) -> Vec<String> {
+            self.other_name.keys().cloned().collect()
+        }
+    }
+
+    impl Prototype for SomeOtherObject {
+        fn prototype(&self) -> Option<Handle> {
+            None
+        }
+
+        fn set_prototype(&mut self, _prototype: Option<Handle>) {}
+    }
+
+    impl Trace for SomeOtherObject {
+        fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+            self.other_name.values().for_each(visit)
+        }
+
+        fn unlink(&mut self) {
+            self.other_name.clear()
+        }
+    }
+
     #[test]
     fn infer_properties() {
         let mut o = SomeObject {
@@ -0,0 +1,55 @@
+//! The properties of strings, numbers and booleans. Primitives aren't objects, reading one of
+//! their properties looks it up on the prototype of their type instead, after the `length` and
+//! characters of a string.
+use crate::runtime::{conversions, exception::*, Interpreter, Value};
+
+/// The primitive types with a prototype, the built-in functions of each one are named
+/// `<type>_<method>`, e.g. `number_toString`.
+pub const TYPES: [&str; 3] = ["string", "number", "boolean"];
+
+/// The methods of the prototype of every primitive type.
+pub const METHODS: [&str; 2] = ["toString", "valueOf"];
+
+/// The property `key` strings have of their own, their `length` in UTF-16 code units or the
+/// character at an index.
+pub fn string_property(string: &str, key: &str) -> Option<Value> {
+    if key == "length" {
+        return Some(Value::Number(string.encode_utf16().count() as f64));
+    }
+    let index = key
+        .parse::<usize>()
+        .ok()
+        .filter(|index| index.to_string() == key)?;
+    let unit = string.encode_utf16().nth(index)?;
+    Some(Value::StringLiteral(String::from_utf16_lossy(&[unit])))
+}
+
+/// Runs the method called `method` of the prototype of the primitive type `type_name` on `this`,
+/// which has to be of that type.
+pub fn call_method(
+    interpreter: &mut Interpreter,
+    type_name: &str,
+    method: &str,
+    this: Value,
+) -> Result<Value, Exception> {
+    let (name, matches) = match type_name {
+        "string" => ("String", matches!(this, Value::StringLiteral(_))),
+        "number" => ("Number", matches!(this, Value::Number(_))),
+        _ => ("Boolean", matches!(this, Value::Boolean(_))),
+    };
+    if !matches {
+        bail!(TypeError(format!(
+            "{}.prototype.{} requires that 'this' be a {}",
+            name, method, name
+        )));
+    }
+
+    match method {
+        "toString" => conversions::to_string(interpreter, this).map(Value::StringLiteral),
+        "valueOf" => Ok(this),
+        _ => bail!(TypeError(format!(
+            "{}.prototype.{} is not a function",
+            name, method
+        ))),
+    }
+}
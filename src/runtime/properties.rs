@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::runtime::Value;
+
+/// The own properties of an object by key, which also remembers the order they were added in,
+/// since that is the order they are enumerated in.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, Default)]
+pub struct Properties {
+    values: HashMap<String, Value>,
+    // Every key of `values`, oldest first
+    order: Vec<String>,
+}
+
+impl Properties {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the property `key`, which keeps its place if it already exists, returning its old
+    /// value.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        if !self.values.contains_key(&key) {
+            self.order.push(key.clone());
+        }
+        self.values.insert(key, value)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.values.get_mut(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Deletes the property `key`, returning its value. Adding it again puts it last.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let value = self.values.remove(key)?;
+        self.order.retain(|k| k != key);
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.order.clear();
+    }
+
+    /// The keys, in the order they were added.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.order.iter()
+    }
+
+    /// The values, in the order their keys were added.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.order.iter().map(move |key| &self.values[key])
+    }
+
+    /// The keys and values, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.order.iter().map(move |key| (key, &self.values[key]))
+    }
+}
+
+impl FromIterator<(String, Value)> for Properties {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut properties = Self::new();
+        for (key, value) in iter {
+            properties.insert(key, value);
+        }
+        properties
+    }
+}
@@ -1,29 +1,28 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashSet, fmt, rc::Rc};
 
-use crate::runtime::{Object, Value};
+use crate::runtime::{object, Handle, Keys, Object, Properties, Prototype, Trace, Value};
 
 #[allow(unused)]
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Object, Clone)]
 #[object_type(String)]
 pub struct JsString {
-    properties: HashMap<String, Value>,
+    properties: Properties,
+    prototype: Option<Handle>,
     str: String,
 }
 
 impl JsString {
     pub fn new(str: &str) -> Self {
         Self {
-            properties: HashMap::new(),
+            properties: Properties::new(),
+            prototype: None,
             str: str.to_owned(),
         }
     }
 
     pub fn boxed(str: &str) -> Box<Self> {
-        Box::new(Self {
-            properties: HashMap::new(),
-            str: str.to_owned(),
-        })
+        Box::new(Self::new(str))
     }
 }
 
@@ -32,3 +31,33 @@ impl fmt::Display for JsString {
         f.write_str(&self.format_properties())
     }
 }
+
+impl Keys for JsString {
+    fn own_keys(&self) -> Vec<String> {
+        object::enumerable_keys(&self.properties, &HashSet::new())
+    }
+}
+
+impl Prototype for JsString {
+    fn prototype(&self) -> Option<Handle> {
+        self.prototype.clone()
+    }
+
+    fn set_prototype(&mut self, prototype: Option<Handle>) {
+        self.prototype = prototype;
+    }
+}
+
+impl Trace for JsString {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.properties.values().for_each(&mut *visit);
+        if let Some(prototype) = self.prototype.as_ref() {
+            visit(&Value::Object(Rc::clone(prototype)));
+        }
+    }
+
+    fn unlink(&mut self) {
+        self.properties.clear();
+        self.prototype = None;
+    }
+}
@@ -1,15 +1,16 @@
 use std::{cell::RefCell, fmt, rc::Rc};
 
-use crate::runtime::Object;
+use crate::runtime::{conversions, Handle, ObjectType};
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Clone)]
 pub enum Value {
     Number(f64),
     Undefined,
+    Null,
     Boolean(bool),
     StringLiteral(String),
-    Object(Rc<RefCell<Box<dyn Object>>>),
+    Object(Handle),
 }
 
 impl Default for Value {
@@ -18,15 +19,92 @@ impl Default for Value {
     }
 }
 
+impl Value {
+    /// The name of the property this value selects when used as a computed key, e.g. `a[1]`
+    /// selects the property named "1".
+    pub fn property_key(&self) -> String {
+        match self {
+            Value::Undefined => String::from("undefined"),
+            other => other.to_string(),
+        }
+    }
+
+    /// Whether two values have the same structure, unlike `==` which compares objects by
+    /// identity. Objects are deep equal when they have the same type and their own enumerable
+    /// properties are deep equal, functions only when they are the same function. Meant for
+    /// assertions on what a script produced.
+    pub fn deep_eq(&self, other: &Value) -> bool {
+        deep_eq(self, other, &mut Vec::new())
+    }
+}
+
+// `comparing` holds the pairs of objects whose comparison is in progress further up, which are
+// assumed equal so that cycles terminate
+fn deep_eq(lhs: &Value, rhs: &Value, comparing: &mut Vec<(Handle, Handle)>) -> bool {
+    let (a, b) = match (lhs, rhs) {
+        (Value::Object(a), Value::Object(b)) => (a, b),
+        _ => return lhs == rhs,
+    };
+    if Rc::ptr_eq(a, b)
+        || comparing
+            .iter()
+            .any(|(x, y)| Rc::ptr_eq(x, a) && Rc::ptr_eq(y, b))
+    {
+        return true;
+    }
+
+    let (a_object, b_object) = (a.borrow(), b.borrow());
+    let object_type = a_object.get_type();
+    if object_type != b_object.get_type() || object_type == ObjectType::Function {
+        return false;
+    }
+    let mut keys = a_object.own_keys();
+    let mut other_keys = b_object.own_keys();
+    keys.sort();
+    other_keys.sort();
+    if keys != other_keys {
+        return false;
+    }
+
+    comparing.push((Rc::clone(a), Rc::clone(b)));
+    let equal = keys
+        .iter()
+        .all(|key| match (a_object.get(key), b_object.get(key)) {
+            (Some(x), Some(y)) => deep_eq(x, y, comparing),
+            (x, y) => x.is_none() && y.is_none(),
+        });
+    comparing.pop();
+    equal
+}
+
+thread_local! {
+    // The objects being formatted further up, one met again refers back to itself and is printed
+    // as "[Circular]" instead
+    static FORMATTING: RefCell<Vec<Handle>> = RefCell::new(Vec::new());
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Value::Number(n) => f.write_str(&n.to_string()),
+            Value::Number(n) => f.write_str(&conversions::number_to_string(*n)),
             Value::Undefined => f.write_str("Undefined"),
+            Value::Null => f.write_str("null"),
             Value::Boolean(b) => f.write_str(&b.to_string()),
             Value::StringLiteral(s) => f.write_str(s),
             Value::Object(o) => {
+                let circular = FORMATTING.with(|formatting| {
+                    let mut formatting = formatting.borrow_mut();
+                    let circular = formatting.iter().any(|object| Rc::ptr_eq(object, o));
+                    if !circular {
+                        formatting.push(Rc::clone(o));
+                    }
+                    circular
+                });
+                if circular {
+                    return f.write_str("[Circular]");
+                }
                 let s = format!("{}", o.borrow());
+                FORMATTING.with(|formatting| formatting.borrow_mut().pop());
                 f.write_str(&s)
             }
         }
@@ -39,14 +117,10 @@ impl PartialEq for Value {
             (Value::Number(n1), Value::Number(n2)) => n1 == n2,
             (Value::Boolean(b1), Value::Boolean(b2)) => b1 == b2,
             (Value::StringLiteral(s1), Value::StringLiteral(s2)) => s1 == s2,
-            // ?: Is undefined == undefined? What about === ?
             (Value::Undefined, Value::Undefined) => true,
-            (Value::Object(_), Value::Object(_)) => {
-                // ?: Two boxes can't point to the same location, that's aliasing.
-                //  So, how can you have object equality? May have to go unsafe with raw
-                //  pointers...
-                false
-            }
+            (Value::Null, Value::Null) => true,
+            // An object is only equal to itself
+            (Value::Object(o1), Value::Object(o2)) => Rc::ptr_eq(o1, o2),
             _ => false,
         }
     }
@@ -0,0 +1,630 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    ir::statement::Block,
+    runtime::{
+        array,
+        bytecode::{Chunk, Compiler, Instruction},
+        class,
+        conversions::to_boolean,
+        exception::*,
+        interpreter, iterator, object, Accessor, Environment, Function, Heap, Interpreter,
+        ObjectType, Scope, Trace, Value,
+    },
+};
+
+/// The state of a single function invocation.
+struct Frame {
+    chunk: Rc<Chunk>,
+    // Index of the next instruction to run
+    ip: usize,
+    // Height of the value stack when the frame was entered, everything above is the frame's
+    base: usize,
+    // The innermost scope of the code being run
+    environment: Scope,
+    // What the caller does with the return value
+    kind: CallKind,
+    // The try statements running in the frame, innermost last
+    handlers: Vec<Handler>,
+}
+
+impl Frame {
+    fn new(chunk: Rc<Chunk>, base: usize, environment: Scope, kind: CallKind) -> Self {
+        Self {
+            chunk,
+            ip: 0,
+            base,
+            environment,
+            kind,
+            handlers: Vec::new(),
+        }
+    }
+}
+
+/// Where an exception thrown in a try block is caught, pushed by [`Instruction::PushHandler`].
+struct Handler {
+    // The instruction catching it
+    target: usize,
+    // Height of the value stack when the try statement started
+    height: usize,
+    // The scope the try statement runs in
+    environment: Scope,
+}
+
+/// Why a function was called, which decides what its frame leaves on the stack.
+enum CallKind {
+    /// The return value is pushed
+    Call,
+    /// A call made by `new` with the object being constructed, which is pushed unless the
+    /// function returns another object
+    Construct(Value),
+    /// A call to a setter, the assigned value is already on the stack so nothing is pushed
+    Set,
+    /// A call to the parent's constructor by `super`, the return value is pushed
+    Super,
+}
+
+/// A stack machine running bytecode produced by the [`Compiler`], an alternative to evaluating
+/// the IR directly with the [`Interpreter`].
+///
+/// Variables resolve through the chain of scopes of the current frame, then the global object.
+/// The wrapped interpreter provides the global scope, the global object and built-in functions,
+/// so both back ends share a single runtime.
+pub struct Vm {
+    interpreter: Interpreter,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Self {
+            interpreter: Interpreter::new(),
+            stack: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Compiles and runs a program.
+    pub fn run(&mut self, program: &Block) -> Result<Value, Exception> {
+        let chunk = Compiler::compile(program)?;
+        self.execute(chunk)
+    }
+
+    /// Runs a compiled program, returning its completion value.
+    pub fn execute(&mut self, chunk: Chunk) -> Result<Value, Exception> {
+        let global_environment = self.interpreter.global_environment();
+        self.frames.push(Frame::new(
+            Rc::new(chunk),
+            0,
+            global_environment,
+            CallKind::Call,
+        ));
+
+        let result = self.run_frames();
+
+        // Top level bindings live on in the global scope, whether or not the program finished
+        self.frames.clear();
+        self.stack.clear();
+
+        result
+    }
+
+    fn run_frames(&mut self) -> Result<Value, Exception> {
+        loop {
+            match self.step() {
+                Ok(Some(value)) => return Ok(value),
+                Ok(None) => (),
+                Err(exception) => self.catch(exception)?,
+            }
+        }
+    }
+
+    /// Runs the next instruction, returning the completion value once the program returns.
+    fn step(&mut self) -> Result<Option<Value>, Exception> {
+        let frame = self.frames.last_mut().unwrap();
+        let instruction = frame.chunk.code[frame.ip].clone();
+        frame.ip += 1;
+
+        match instruction {
+            Instruction::Constant(i) => {
+                let constant = self.frame().chunk.constants[i].clone();
+                self.stack.push(constant);
+            }
+            Instruction::Undefined => self.stack.push(Value::Undefined),
+            Instruction::Pop => {
+                self.pop();
+            }
+            Instruction::Dup => {
+                let top = self.peek().clone();
+                self.stack.push(top);
+            }
+            Instruction::Dup2 => {
+                let below = self.stack.len() - 2;
+                self.stack.extend_from_within(below..);
+            }
+            Instruction::Bury(depth) => {
+                let top = self.pop();
+                let below = self.stack.len() - depth;
+                self.stack.insert(below, top);
+            }
+            Instruction::GetVariable(i) => {
+                let name = self.name(i);
+                let value = self.variable(&name)?;
+                self.stack.push(value);
+            }
+            Instruction::GetVariableIfDeclared(i) => {
+                let name = self.name(i);
+                let value = interpreter::undeclared_as_undefined(&name, self.variable(&name))?;
+                self.stack.push(value);
+            }
+            Instruction::SetVariable(i) => {
+                let name = self.name(i);
+                let value = self.peek().clone();
+                self.assign_variable(&name, value)?;
+            }
+            Instruction::DeclareVariable(i) => {
+                let name = self.name(i);
+                let value = self.pop();
+                self.frame().environment.borrow_mut().declare(name, value);
+            }
+            Instruction::DeclareConstant(i) => {
+                let name = self.name(i);
+                let value = self.pop();
+                let environment = &self.frame().environment;
+                environment.borrow_mut().declare_constant(name, value);
+            }
+            Instruction::HoistVar(i) => {
+                let name = self.name(i);
+                self.frame().environment.borrow_mut().hoist_var(&name);
+            }
+            Instruction::SetVar(i) => {
+                let name = self.name(i);
+                let value = self.peek().clone();
+                let environment = Rc::clone(&self.frame().environment);
+                self.interpreter.write_var(&environment, &name, value);
+            }
+            Instruction::HoistLexical(i) => {
+                let name = self.name(i);
+                self.frame().environment.borrow_mut().hoist_lexical(&name);
+            }
+            Instruction::SetGlobal(i) => {
+                let value = self.peek().clone();
+                self.interpreter.set_global_property(&self.name(i), value);
+            }
+            Instruction::HoistGlobal(i) => self.interpreter.hoist_global_var(&self.name(i)),
+            Instruction::GetProperty(i) => {
+                let object = self.pop();
+                self.push_property(object, &self.name(i))?;
+            }
+            Instruction::GetIndex => {
+                let key = self.pop().property_key();
+                let object = self.pop();
+                self.push_property(object, &key)?;
+            }
+            Instruction::GetMethod(i) => {
+                // The object stays below the method, to be the receiver of the call
+                let object = self.peek().clone();
+                self.push_property(object, &self.name(i))?;
+            }
+            Instruction::SetProperty(i) => {
+                let object = self.pop();
+                let value = self.peek().clone();
+                self.assign_property(object, &self.name(i), value)?;
+            }
+            Instruction::DeleteProperty(i) => {
+                let object = self.pop();
+                let deleted = self.interpreter.delete_property(&object, &self.name(i))?;
+                self.stack.push(Value::Boolean(deleted));
+            }
+            Instruction::DeleteIndex => {
+                let key = self.pop().property_key();
+                let object = self.pop();
+                let deleted = self.interpreter.delete_property(&object, &key)?;
+                self.stack.push(Value::Boolean(deleted));
+            }
+            Instruction::SetIndex => {
+                let key = self.pop().property_key();
+                let object = self.pop();
+                let value = self.peek().clone();
+                self.assign_property(object, &key, value)?;
+            }
+            Instruction::GetSuper(i) => {
+                let receiver = self.pop();
+                let home = class::home_object(&self.frame().environment);
+                let base = class::super_base(&home)?;
+                let value = base
+                    .borrow()
+                    .lookup(&self.name(i))
+                    .ok_or_else(|| ReferenceError(self.name(i)))?;
+                self.push_value_of(value, receiver)?;
+            }
+            Instruction::SuperConstructor => {
+                let home = class::home_object(&self.frame().environment);
+                let constructor = class::super_constructor(&home)?;
+                self.stack.push(constructor);
+            }
+            Instruction::Unary(op) => {
+                let operand = self.pop();
+                let value = op.apply(&mut self.interpreter, operand)?;
+                self.stack.push(value);
+            }
+            Instruction::Binary(op) => {
+                let rhs = self.pop();
+                let lhs = self.pop();
+                let value = op.apply(&mut self.interpreter, lhs, rhs)?;
+                self.stack.push(value);
+            }
+            Instruction::Jump(to) => self.frames.last_mut().unwrap().ip = to,
+            Instruction::JumpIfFalse(to) => {
+                if !to_boolean(&self.pop()) {
+                    self.frames.last_mut().unwrap().ip = to;
+                }
+            }
+            Instruction::ShortCircuit(op, to) => {
+                match op.short_circuits(self.stack.last().unwrap()) {
+                    true => self.frames.last_mut().unwrap().ip = to,
+                    false => {
+                        self.pop();
+                    }
+                }
+            }
+            Instruction::EnterScope => {
+                let frame = self.frames.last_mut().unwrap();
+                let parent = Rc::clone(&frame.environment);
+                frame.environment = Environment::new(HashMap::new(), Some(parent));
+            }
+            Instruction::LeaveScope => {
+                let frame = self.frames.last_mut().unwrap();
+                let parent = frame.environment.borrow().parent();
+                frame.environment = parent.expect("Cannot leave the global scope");
+            }
+            Instruction::CopyScope => {
+                let frame = self.frames.last_mut().unwrap();
+                frame.environment = Environment::copy(&frame.environment);
+            }
+            Instruction::MakeFunction(i) => {
+                let template = Rc::clone(&self.frame().chunk.functions[i]);
+                let parameters = template.parameters.clone();
+                let body = template.body.clone();
+                let mut function = match template.is_arrow {
+                    true => Function::arrow(parameters, body),
+                    false => Function::new(template.name.clone(), parameters, body),
+                };
+                function.bytecode = Some(Rc::clone(&template.chunk));
+                function.environment = Some(Rc::clone(&self.frame().environment));
+                let value = match template.is_arrow {
+                    true => self.interpreter.new_method(function),
+                    false => self.interpreter.new_function(function),
+                };
+                self.push_allocated(value);
+            }
+            Instruction::MakeObject(i) => {
+                let keys = self.frame().chunk.shapes[i].clone();
+                let values = self.stack.split_off(self.stack.len() - keys.len());
+                let properties = keys.into_iter().zip(values).collect();
+                let value = self.interpreter.new_object(properties);
+                self.push_allocated(value);
+            }
+            Instruction::MakeArray(length) => {
+                let elements = self.stack.split_off(self.stack.len() - length);
+                let value = self.interpreter.new_array(elements);
+                self.push_allocated(value);
+            }
+            Instruction::MakeClass(i) => {
+                let definition = Rc::clone(&self.frame().chunk.classes[i]);
+                let parent = match definition.has_superclass {
+                    true => Some(self.pop()),
+                    false => None,
+                };
+                let environment = Rc::clone(&self.frame().environment);
+                let value = definition.create(&mut self.interpreter, parent, environment)?;
+                self.push_allocated(value);
+            }
+            Instruction::Call(argc, callee) => {
+                let arguments = self.stack.split_off(self.stack.len() - argc);
+                let function = self.pop();
+                let not_callable = format!("\'{}\' is not callable", self.name(callee));
+                self.call(
+                    function,
+                    Value::Undefined,
+                    arguments,
+                    not_callable,
+                    CallKind::Call,
+                )?;
+            }
+            Instruction::CallMethod(argc, callee) => {
+                let arguments = self.stack.split_off(self.stack.len() - argc);
+                let function = self.pop();
+                let this = self.pop();
+                let not_callable = format!("\'{}\' is not callable", self.name(callee));
+                self.call(function, this, arguments, not_callable, CallKind::Call)?;
+            }
+            Instruction::CallSuper(argc) => {
+                let arguments = self.stack.split_off(self.stack.len() - argc);
+                let function = self.pop();
+                let this = self.pop();
+                let not_callable = String::from("'super' is not callable");
+                self.call(function, this, arguments, not_callable, CallKind::Super)?;
+            }
+            Instruction::Construct(argc) => {
+                let arguments = self.stack.split_off(self.stack.len() - argc);
+                let constructor = self.pop();
+                let instance = self.interpreter.new_instance(&constructor)?;
+                // Only functions written in JavaScript get this far, so a frame is pushed
+                self.call(
+                    constructor,
+                    instance.clone(),
+                    arguments,
+                    String::from("Value is not a constructor"),
+                    CallKind::Construct(instance),
+                )?;
+            }
+            Instruction::Return => {
+                let value = self.pop();
+                // The program itself is the bottom frame, keep it for its global scope
+                if self.frames.len() == 1 {
+                    return Ok(Some(value));
+                }
+                let frame = self.frames.pop().unwrap();
+                self.stack.truncate(frame.base);
+                match (frame.kind, value) {
+                    (CallKind::Set, _) => (),
+                    (CallKind::Construct(instance), value)
+                        if !matches!(value, Value::Object(_)) =>
+                    {
+                        self.stack.push(instance)
+                    }
+                    (_, value) => self.stack.push(value),
+                }
+            }
+            Instruction::Throw => {
+                let value = self.pop();
+                bail!(Thrown(value))
+            }
+            Instruction::PushHandler(target) => {
+                let height = self.stack.len();
+                let frame = self.frames.last_mut().unwrap();
+                let environment = Rc::clone(&frame.environment);
+                frame.handlers.push(Handler {
+                    target,
+                    height,
+                    environment,
+                });
+            }
+            Instruction::PopHandler => {
+                self.frames.last_mut().unwrap().handlers.pop();
+            }
+            Instruction::EnumerateKeys => {
+                let value = self.pop();
+                let keys = object::for_in_keys(&value)
+                    .into_iter()
+                    .map(Value::StringLiteral)
+                    .collect();
+                let keys = self.interpreter.new_array(keys);
+                let value = self.interpreter.new_array_iterator(keys);
+                self.push_allocated(value);
+            }
+            Instruction::GetIterator => {
+                let iterable = self.pop();
+                let (receiver, method) = self.interpreter.iterator_method(iterable)?;
+                self.stack.push(receiver);
+                self.stack.push(Value::Object(method));
+            }
+            Instruction::IteratorValue(to) => {
+                let result = self.pop();
+                match iterator::result_value(&result)? {
+                    Some(value) => self.stack.push(value),
+                    None => self.frames.last_mut().unwrap().ip = to,
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Continues at the innermost handler of an exception, leaving the frames that don't have
+    /// one, or returns the exception if nothing catches it.
+    fn catch(&mut self, exception: Exception) -> Result<(), Exception> {
+        while let Some(frame) = self.frames.last_mut() {
+            if let Some(handler) = frame.handlers.pop() {
+                frame.ip = handler.target;
+                frame.environment = handler.environment;
+                self.stack.truncate(handler.height);
+                let value = exception.into_value(&mut self.interpreter);
+                self.push_allocated(value);
+                return Ok(());
+            }
+            self.frames.pop();
+        }
+        Err(exception)
+    }
+
+    /// Calls a function object, either by running a built-in to completion, or by pushing a
+    /// frame that the main loop picks up.
+    fn call(
+        &mut self,
+        function: Value,
+        this: Value,
+        arguments: Vec<Value>,
+        not_callable: String,
+        kind: CallKind,
+    ) -> Result<(), Exception> {
+        let function = match function {
+            Value::Object(o) if o.borrow().get_type() == ObjectType::Function => o,
+            _ => bail!(TypeError(not_callable)),
+        };
+
+        // Only `new` and `super` can run the constructor of a class
+        if matches!(kind, CallKind::Call | CallKind::Set) {
+            interpreter::check_not_class_constructor(&function)?;
+        }
+
+        let mut function_borrow = function.borrow_mut();
+        let function = function_borrow.as_function();
+
+        if function.is_built_in() {
+            let name = function.name.clone().unwrap();
+            drop(function_borrow);
+            let value = self.interpreter.handle_built_in(&name, this, arguments)?;
+            if !matches!(kind, CallKind::Set) {
+                self.stack.push(value);
+            }
+        } else {
+            // Missing arguments are undefined, extra arguments are only in `arguments`
+            let all_arguments = arguments.clone();
+            let mut arguments = arguments.into_iter();
+            let mut context: HashMap<String, Value> = function
+                .parameters
+                .iter()
+                .map(|formal| (formal.clone(), arguments.next().unwrap_or_default()))
+                .collect();
+            if !function.is_arrow() {
+                context.insert("this".to_owned(), this);
+                let arguments_array = self.interpreter.new_array(all_arguments);
+                context.insert("arguments".to_owned(), arguments_array);
+            }
+
+            // Functions made by the interpreter are compiled when first called here
+            let chunk = match function.bytecode.as_ref() {
+                Some(chunk) => Rc::clone(chunk),
+                None => {
+                    let chunk = Rc::new(Compiler::compile_function(&function.body)?);
+                    function.bytecode = Some(Rc::clone(&chunk));
+                    chunk
+                }
+            };
+            let parent = function
+                .environment
+                .clone()
+                .unwrap_or_else(|| self.interpreter.global_environment());
+            drop(function_borrow);
+            let environment = Environment::new(context, Some(parent));
+            self.frames
+                .push(Frame::new(chunk, self.stack.len(), environment, kind));
+        }
+        success!(())
+    }
+
+    pub fn heap(&self) -> &Heap {
+        self.interpreter.heap()
+    }
+
+    /// Frees every object that isn't reachable from the value stack, the scopes of the running
+    /// frames or those of the wrapped interpreter, returning how many were freed.
+    pub fn collect_garbage(&mut self) -> usize {
+        let mut roots = self.stack.clone();
+        for frame in self.frames.iter() {
+            frame
+                .environment
+                .borrow()
+                .trace(&mut |value| roots.push(value.clone()));
+            for handler in frame.handlers.iter() {
+                handler
+                    .environment
+                    .borrow()
+                    .trace(&mut |value| roots.push(value.clone()));
+            }
+        }
+
+        self.interpreter.collect_garbage_with(roots.iter())
+    }
+
+    /// Pushes a newly allocated object. Everything the VM holds is on its stack or in its scopes,
+    /// so any allocation is a safe point to collect.
+    fn push_allocated(&mut self, object: Value) {
+        self.stack.push(object);
+        if self.interpreter.heap().should_collect() {
+            self.collect_garbage();
+        }
+    }
+
+    /// Pushes the property `key` of `object`. For an accessor property, its getter is called
+    /// instead, and pushes the value once it returns.
+    fn push_property(&mut self, object: Value, key: &str) -> Result<(), Exception> {
+        let value = self.interpreter.lookup_property(&object, key)?;
+        self.push_value_of(value, object)
+    }
+
+    /// Pushes `value`, unless it is an accessor, whose getter is called on `receiver`.
+    fn push_value_of(&mut self, value: Value, receiver: Value) -> Result<(), Exception> {
+        match Accessor::functions(&value) {
+            None => self.stack.push(value),
+            Some((Some(getter), _)) => self.call(
+                Value::Object(getter),
+                receiver,
+                Vec::new(),
+                String::from("Getter is not callable"),
+                CallKind::Call,
+            )?,
+            Some((None, _)) => self.stack.push(Value::Undefined),
+        }
+        success!(())
+    }
+
+    /// Assigns to the property `key` of `object`. For an accessor property, its setter is called
+    /// instead.
+    fn assign_property(&mut self, object: Value, key: &str, value: Value) -> Result<(), Exception> {
+        let object = match object {
+            Value::Object(o) => o,
+            Value::Undefined | Value::Null => {
+                bail!(TypeError("Variable is not an object".to_owned()))
+            }
+            // Primitives have no properties of their own to assign to
+            _ => return Ok(()),
+        };
+        let existing = object.borrow().lookup(key);
+        match existing.as_ref().and_then(Accessor::functions) {
+            None => {
+                let value = array::assigned_value(&mut self.interpreter, &object, key, value)?;
+                object.borrow_mut().set(key, value)
+            }
+            Some((_, Some(setter))) => self.call(
+                Value::Object(setter),
+                Value::Object(object),
+                vec![value],
+                String::from("Setter is not callable"),
+                CallKind::Set,
+            ),
+            Some((_, None)) => Err(TypeError(format!(
+                "Cannot set property {} which has only a getter",
+                key
+            ))),
+        }
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().unwrap()
+    }
+
+    fn name(&self, i: usize) -> String {
+        self.frame().chunk.names[i].clone()
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("Value stack underflow")
+    }
+
+    fn peek(&self) -> &Value {
+        self.stack.last().expect("Value stack underflow")
+    }
+
+    fn variable(&mut self, name: &str) -> Result<Value, Exception> {
+        match Environment::read(&self.frame().environment, name)? {
+            Some(value) => Ok(value),
+            None => self.interpreter.global_property(name),
+        }
+    }
+
+    fn assign_variable(&mut self, name: &str, value: Value) -> Result<Value, Exception> {
+        match Environment::write(&self.frame().environment, name, value.clone())? {
+            true => Ok(value),
+            false => self.interpreter.assign_variable(name, value),
+        }
+    }
+}
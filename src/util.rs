@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use std::io::Read;
 use std::rc::Rc;
 
-use crate::runtime::Object;
+use crate::runtime::{Handle, Object};
 
 pub fn make_indent(indent: u32) -> String {
     let mut indents = String::new();
@@ -31,34 +31,34 @@ pub fn get_input() -> String {
     }
 }
 
-pub fn wrap_object(obj: Box<dyn Object>) -> Rc<RefCell<Box<dyn Object>>> {
+pub fn wrap_object(obj: Box<dyn Object>) -> Handle {
     Rc::new(RefCell::new(obj))
 }
 
 pub trait Bundled {
-    fn bundled() -> Rc<RefCell<Box<dyn Object>>>
+    fn bundled() -> Handle
     where
         Self: Default;
 
-    fn bundle(self) -> Rc<RefCell<Box<dyn Object>>>;
+    fn bundle(self) -> Handle;
 
     fn value(self) -> crate::runtime::Value;
 
-    fn wrap(self: Box<Self>) -> Rc<RefCell<Box<dyn Object>>>;
+    fn wrap(self: Box<Self>) -> Handle;
 }
 
 impl<O> Bundled for O
 where
     O: 'static + Object,
 {
-    fn bundled() -> Rc<RefCell<Box<dyn Object>>>
+    fn bundled() -> Handle
     where
         Self: Default,
     {
         Rc::new(RefCell::new(Box::new(Self::default())))
     }
 
-    fn bundle(self) -> Rc<RefCell<Box<dyn Object>>> {
+    fn bundle(self) -> Handle {
         Rc::new(RefCell::new(Box::new(self)))
     }
 
@@ -66,12 +66,12 @@ where
         crate::runtime::Value::Object(Rc::new(RefCell::new(Box::new(self))))
     }
 
-    fn wrap(self: Box<Self>) -> Rc<RefCell<Box<dyn Object>>> {
+    fn wrap(self: Box<Self>) -> Handle {
         Rc::new(RefCell::new(self))
     }
 }
 
-pub fn bundle_object<O: 'static + Object>(obj: O) -> Rc<RefCell<Box<dyn Object>>> {
+pub fn bundle_object<O: 'static + Object>(obj: O) -> Handle {
     Rc::new(RefCell::new(Box::new(obj)))
 }
 
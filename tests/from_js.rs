@@ -1,6 +1,6 @@
 use std::env;
 
-use jsrs::prelude::*;
+use jsrs::{prelude::*, runtime::bytecode::Compiler};
 
 fn validate_output(file_name: &str, expected: Result<Value, Exception>) {
     let verbose = match env::var("VERBOSE") {
@@ -10,7 +10,7 @@ fn validate_output(file_name: &str, expected: Result<Value, Exception>) {
 
     let input = std::fs::read_to_string(&format!("tests/input/{}.js", file_name)).unwrap();
 
-    let program = parse_program(&input);
+    let program = parse_program(&input).unwrap();
 
     if verbose {
         println!("{}", program.print());
@@ -28,6 +28,23 @@ fn validate_output(file_name: &str, expected: Result<Value, Exception>) {
     }
 
     assert_eq!(result, expected);
+
+    // The VM has to agree with the tree-walker on every program
+    let program = parse_program(&input).unwrap();
+    let chunk = Compiler::compile(&program).unwrap_or_else(|e| panic!("{}", e));
+    if verbose {
+        println!("{}", chunk.disassemble());
+    }
+    assert_eq!(Vm::new().execute(chunk), expected);
+}
+
+fn validate_parse_error(file_name: &str, construct: &str, line: usize) {
+    let input = std::fs::read_to_string(&format!("tests/input/{}.js", file_name)).unwrap();
+
+    let error = parse_program(&input).unwrap_err();
+
+    assert_eq!(error.construct, construct);
+    assert_eq!(error.line, line);
 }
 
 #[test]
@@ -129,7 +146,7 @@ fn exception() {
 fn member() {
     validate_output(
         "member",
-        Err(Exception::TypeError("Value is not an object".to_owned())),
+        Err(Exception::TypeError("'console.log' is not callable".to_owned())),
     );
 }
 
@@ -137,3 +154,582 @@ fn member() {
 fn objects() {
     validate_output("object", Ok(Value::Number(34f64)));
 }
+
+#[test]
+fn unsupported_construct() {
+    validate_parse_error("unsupported", "with statement", 3);
+}
+
+#[test]
+fn nested_unsupported() {
+    validate_parse_error("nested_unsupported", "with statement", 1);
+
+    let input = std::fs::read_to_string("tests/input/nested_unsupported.js").unwrap();
+    let error = parse_program(&input).unwrap_err();
+    assert_eq!(
+        error.message,
+        "with statement is not supported in arrow function in anonymous function in function 'area'"
+    );
+}
+
+#[test]
+fn syntax_error() {
+    validate_parse_error("syntax_error", "=", 2);
+}
+
+#[test]
+fn nullish_coalescing_is_unparseable() {
+    validate_parse_error("nullish_coalescing", "'??' operator", 2);
+}
+
+#[test]
+fn logical_assignment_is_unparseable() {
+    validate_parse_error("logical_assignment", "'||=' operator", 2);
+    validate_parse_error("nullish_assignment", "'??=' operator", 2);
+}
+
+#[test]
+fn repl_keeps_state() {
+    let mut repl = jsrs::repl::Repl::new();
+
+    assert_eq!(repl.feed("let x = 5"), Some(Ok(Value::Undefined)));
+    assert_eq!(repl.feed("function add(y) {"), None);
+    assert_eq!(repl.feed("    return x + y"), None);
+    assert!(matches!(repl.feed("}"), Some(Ok(Value::Object(_)))));
+
+    // An exception doesn't lose what was declared before it
+    assert!(repl.feed("missing()").unwrap().is_err());
+    assert_eq!(repl.feed("add(2)"), Some(Ok(Value::Number(7f64))));
+}
+
+#[test]
+fn nested_loops() {
+    validate_output("nested_loops", Ok(Value::Number(1000f64)));
+}
+
+#[test]
+fn collects_cycles() {
+    let input = std::fs::read_to_string("tests/input/cycles.js").unwrap();
+
+    // Two parent and child pairs, plus a holder, its closure, the closure's prototype object and
+    // the arguments of the call the closure's scope belongs to, `kept` is still in scope
+    let mut interpreter = Interpreter::new();
+    interpreter.run(parse_program(&input).unwrap()).unwrap();
+    let before = interpreter.heap().stats().live;
+    assert_eq!(interpreter.collect_garbage(), 8);
+    assert_eq!(interpreter.heap().stats().live, before - 8);
+    assert_eq!(interpreter.collect_garbage(), 0);
+
+    let mut vm = Vm::new();
+    vm.run(&parse_program(&input).unwrap()).unwrap();
+    assert_eq!(vm.collect_garbage(), 8);
+    assert_eq!(vm.heap().stats().collected, 8);
+}
+
+#[test]
+fn try_catch() {
+    validate_output("try_catch", Ok(Value::Number(11011f64)));
+}
+
+#[test]
+fn try_finally_jumps() {
+    validate_output("try_finally_jumps", Ok(Value::Number(15f64)));
+}
+
+#[test]
+fn caught_errors() {
+    validate_output("caught_errors", Ok(Value::Number(1111f64)));
+}
+
+#[test]
+fn exception_unwinding() {
+    validate_output(
+        "exception_unwinding",
+        Ok(Value::StringLiteral(String::from(
+            "deep inner outer returned of1 of2 after cleanup 2 outer",
+        ))),
+    );
+}
+
+#[test]
+fn uncaught_throw() {
+    validate_output("uncaught_throw", Err(Exception::Thrown(Value::Number(2f64))));
+}
+
+#[test]
+fn closures() {
+    validate_output("closures", Ok(Value::Number(105f64)));
+}
+
+#[test]
+fn closure_isolation() {
+    validate_output(
+        "closure_isolation",
+        Err(Exception::ReferenceError("secret".to_owned())),
+    );
+}
+
+#[test]
+fn arrays() {
+    validate_output("arrays", Ok(Value::Number(43f64)));
+}
+
+#[test]
+fn array_methods() {
+    validate_output(
+        "array_methods",
+        Ok(Value::StringLiteral(
+            "5 5 1 4 2,3 2,3 0,x,y,z,4 321 1--3 0xyz4239 2 -1 4".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn array_arguments() {
+    validate_output(
+        "array_arguments",
+        Ok(Value::StringLiteral(
+            "1,2,3 2,3 1,2 1,2,3 0:1,2,3 0:1,2,3 2,3:1 3 0 10203 1null2 1,2 1,2 2,3".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn cyclic_join() {
+    validate_output(
+        "cyclic_join",
+        Ok(Value::StringLiteral("1--,2 | 1,,,2 | 1,,,2 | 1,,,2".to_owned())),
+    );
+}
+
+#[test]
+fn prototypes() {
+    validate_output(
+        "prototypes",
+        Ok(Value::StringLiteral(
+            "Rex says woof, Rock says ..., Bit says woof, Bit wags, Bit says yip, Rex says woof, \
+             true, false, shared, true, Tom says meow"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn prototype_cycle() {
+    validate_output(
+        "prototype_cycle",
+        Err(Exception::TypeError("Cyclic __proto__ value".to_owned())),
+    );
+}
+
+#[test]
+fn constructors() {
+    validate_output(
+        "constructors",
+        Ok(Value::StringLiteral(
+            "33 true false true false true true true true true true true true".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn constructor_last_statement() {
+    validate_output(
+        "constructor_last_statement",
+        Ok(Value::StringLiteral("true p 1 true c 2 true 3".to_owned())),
+    );
+}
+
+#[test]
+fn not_a_constructor() {
+    validate_output(
+        "not_a_constructor",
+        Err(Exception::TypeError("Value is not a constructor".to_owned())),
+    );
+}
+
+#[test]
+fn classes() {
+    validate_output(
+        "classes",
+        Ok(Value::StringLiteral(
+            "rectangle with area 25; 2x3; 5x5; square: rectangle with area 16; \
+             square: unit with area 1; Rex makes a sound, woof; true; true; true; true; false; \
+             false"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn class_extends_non_constructor() {
+    validate_output(
+        "class_extends_non_constructor",
+        Err(Exception::TypeError(
+            "Class extends value is not a constructor".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn loops() {
+    validate_output(
+        "loops",
+        Ok(Value::StringLiteral("5 243 1 3 4 128 4".to_owned())),
+    );
+}
+
+#[test]
+fn labels() {
+    validate_output(
+        "labels",
+        Ok(Value::StringLiteral("42 13 3 6 7 1 8".to_owned())),
+    );
+}
+
+#[test]
+fn finally_continue() {
+    validate_output("finally_continue", Ok(Value::StringLiteral("9 5".to_owned())));
+}
+
+#[test]
+fn for_in_of() {
+    validate_output(
+        "for_in_of",
+        Ok(Value::StringLiteral(
+            "x,y own,shadowed,inherited 0,1,2 2 8 heyabc 1,4,9 c".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn iterator_results() {
+    validate_output(
+        "iterator_results",
+        Ok(Value::StringLiteral("2,1,0 0 false 1".to_owned())),
+    );
+}
+
+#[test]
+fn property_order() {
+    validate_output(
+        "property_order",
+        Ok(Value::StringLiteral(
+            "1 2 10 zebra mango banana apple own first inherited".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn for_of_non_iterable() {
+    validate_output(
+        "for_of_non_iterable",
+        Err(Exception::TypeError("5 is not iterable".to_owned())),
+    );
+}
+
+#[test]
+fn switch() {
+    validate_output(
+        "switch",
+        Ok(Value::StringLiteral(
+            "one-default-two two three default-two default-two 3 3 1 one true".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn declarations() {
+    validate_output(
+        "declarations",
+        Ok(Value::StringLiteral("true,1,2 inner outer 9 3 2 5".to_owned())),
+    );
+}
+
+#[test]
+fn global_bindings() {
+    validate_output(
+        "global_bindings",
+        Ok(Value::StringLiteral(
+            "undefinedtrue 12 12 2 3 undefined undefined".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn temporal_dead_zone() {
+    validate_output(
+        "temporal_dead_zone",
+        Err(Exception::UninitializedError("x".to_owned())),
+    );
+}
+
+#[test]
+fn const_assignment() {
+    validate_output(
+        "const_assignment",
+        Err(Exception::TypeError(
+            "Assignment to constant variable \"x\"".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn nested_functions() {
+    validate_output(
+        "nested_functions",
+        Ok(Value::StringLiteral("8 2 false 7 2".to_owned())),
+    );
+}
+
+#[test]
+fn nested_function_scope() {
+    validate_output(
+        "nested_function_scope",
+        Err(Exception::ReferenceError("inner".to_owned())),
+    );
+}
+
+#[test]
+fn coercion() {
+    validate_output(
+        "coercion",
+        Ok(Value::StringLiteral(
+            "12 2 true true true false false 43 named true true false 1,2,3 true false false 16 \
+             [object Object] 1e+21 0.000001 Infinity 3"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn nan_globals() {
+    validate_output(
+        "nan_globals",
+        Ok(Value::StringLiteral(
+            "false true true false true false true false true number true true".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn to_primitive() {
+    validate_output(
+        "to_primitive",
+        Err(Exception::TypeError(
+            "Cannot convert object to primitive value".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn object_identity() {
+    validate_output(
+        "object_identity",
+        Ok(Value::StringLiteral("true false true true 1 original".to_owned())),
+    );
+}
+
+#[test]
+fn deep_equality() {
+    let run = |source: &str| Interpreter::new().run(parse_program(source).unwrap()).unwrap();
+
+    // Separate objects, from separate interpreters even, are never equal
+    let nested = run("let o = {list: [1, {x: 2}], name: \"n\"}\no");
+    let same = run("let o = {name: \"n\", list: [1, {x: 2}]}\no");
+    assert_ne!(nested, same);
+    assert!(nested.deep_eq(&same));
+    assert!(nested.deep_eq(&nested));
+
+    assert!(!nested.deep_eq(&run("let o = {list: [1, {x: 3}], name: \"n\"}\no")));
+    assert!(!nested.deep_eq(&run("let o = {list: [1, {x: 2}]}\no")));
+    assert!(!run("let o = [1, 2]\no").deep_eq(&run("let o = [1, 2, 3]\no")));
+    assert!(!run("let o = []\no").deep_eq(&run("let o = {}\no")));
+
+    // Cycles are followed only once
+    let cycle = run("let o = {}\no.self = o\no");
+    assert!(cycle.deep_eq(&run("let o = {}\no.self = o\no")));
+}
+
+#[test]
+fn unary() {
+    validate_output(
+        "unary",
+        Ok(Value::StringLiteral(
+            "number,string,boolean,undefined,object,object,object,function,undefined -3 4 true true -6 0  true false true 1,,3 false true false false 1 true"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn operators() {
+    validate_output(
+        "operators",
+        Ok(Value::StringLiteral(
+            "1 -1 1.5 1024 0.5 NaN -2147483648 -4 15 4294967295 2 1 7 6 1 2 true true false true true false 2071478856"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn logical() {
+    validate_output(
+        "logical",
+        Ok(Value::StringLiteral("fallback first both   0 3 2 few no 2 2".to_owned())),
+    );
+}
+
+#[test]
+fn compound_assignment() {
+    validate_output(
+        "compound_assignment",
+        Ok(Value::StringLiteral(
+            "8 1 22 1 2 42,3,2 3 3 a1 6 number 15".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn const_update() {
+    validate_output(
+        "const_update",
+        Err(Exception::TypeError(
+            "Assignment to constant variable \"count\"".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn member_chains() {
+    validate_output(
+        "member_chains",
+        Ok(Value::StringLiteral(
+            "main main 443 3 8080 30 backup  undefined 4 20 yes true ".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn implicit_return() {
+    validate_output("implicit_return", Ok(Value::Undefined));
+}
+
+#[test]
+fn callees() {
+    validate_output(
+        "callees",
+        Ok(Value::StringLiteral(
+            "immediate 5 local nested nested first".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn not_callable() {
+    validate_output(
+        "not_callable",
+        Err(Exception::TypeError(
+            "'config.server.start' is not callable".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn arrow_functions() {
+    validate_output(
+        "arrow_functions",
+        Ok(Value::StringLiteral(
+            "42 3 1 apple 3 pears undefined 10,20,30 2 3 1,3 function false undefined undefined"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn arrow_not_constructor() {
+    validate_output(
+        "arrow_not_constructor",
+        Err(Exception::TypeError("Value is not a constructor".to_owned())),
+    );
+}
+
+#[test]
+fn loop_closures() {
+    validate_output(
+        "loop_closures",
+        Ok(Value::StringLiteral("0 1 2 0:0 2:4 4:16 3 3 3".to_owned())),
+    );
+}
+
+#[test]
+fn array_indices() {
+    validate_output(
+        "array_indices",
+        Ok(Value::StringLiteral(
+            "1000000001 3 undefined | 0 max over | 0,1,5000 | 2001 1999 d d 2000 | \
+             2000000000 undefined | 5000 | ,8"
+                .to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn array_length_range() {
+    validate_output(
+        "array_length_range",
+        Err(Exception::RangeError("Invalid array length".to_owned())),
+    );
+}
+
+#[test]
+fn circular_print() {
+    validate_output("circular_print", Ok(Value::StringLiteral("o".to_owned())));
+}
+
+#[test]
+fn assignment_order() {
+    validate_output(
+        "assignment_order",
+        Ok(Value::StringLiteral(
+            "1,NaN,2 3 11 object,key,value,object,key,value,object".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn primitive_members() {
+    validate_output(
+        "primitive_members",
+        Ok(Value::StringLiteral(
+            "3 b c undefined 5 51 true function false x true 0 undefined undefined".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn null_member() {
+    validate_output(
+        "null_member",
+        Err(Exception::TypeError("Value is not an object".to_owned())),
+    );
+}
+
+#[test]
+fn class_call_without_new() {
+    validate_output(
+        "class_call_without_new",
+        Err(Exception::TypeError(
+            "Class constructor A cannot be invoked without 'new'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn block_functions() {
+    validate_output(
+        "block_functions",
+        Ok(Value::StringLiteral("undefined inner parameter let top".to_owned())),
+    );
+}
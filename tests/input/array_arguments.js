@@ -0,0 +1,30 @@
+// Index and count arguments are converted to integers, with NaN as 0
+let results = [];
+results.push([1, 2, 3].slice(NaN).join(","));
+results.push([1, 2, 3].slice("1").join(","));
+results.push([1, 2, 3].slice(0.9, "2.5").join(","));
+results.push([1, 2, 3].slice(-Infinity, Infinity).join(","));
+
+let a = [1, 2, 3];
+results.push(a.splice().length + ":" + a.join(","));
+results.push(a.splice(1, undefined).length + ":" + a.join(","));
+results.push(a.splice("1").join(",") + ":" + a.join(","));
+
+results.push([1, 2, 3, 2].indexOf(2, "2"), [1, 2, 3].indexOf(1, NaN));
+results.push([1, 2, 3].join(0), [1, 2].join(null), [1, 2].join(undefined));
+
+let b = [1, 2, 3, 4];
+b.length = "2";
+results.push(b.join(","));
+
+// Converting an argument may use the array itself
+let c = [1, 2, 3];
+let start = {
+    valueOf: function () {
+        c.push(4);
+        return 1;
+    },
+};
+results.push(c.slice(start).join(","));
+
+results.join(" ");
@@ -0,0 +1,35 @@
+// Writing far past the end doesn't store every element in between
+let a = [1, 2]
+a[1e9] = 3
+let far = a.length + " " + a[1e9] + " " + a[5]
+
+// Larger numbers than an index can be are ordinary properties
+let b = []
+b[4294967295] = "max"
+b[4294967296] = "over"
+let names = b.length + " " + b[4294967295] + " " + b["4294967296"]
+
+// Keys of elements stored apart are enumerated after the others
+let c = [7]
+c[5000] = 8
+c[1] = 9
+let keys = []
+for (let key in c) {
+    keys.push(key)
+}
+
+// Filling in the gap brings them together again
+let d = []
+d[2000] = "d"
+for (let i = 0; i < 2000; i++) {
+    d[i] = i
+}
+let joined = d.length + " " + d[1999] + " " + d[2000] + " " + d.pop() + " " + d.length
+
+// Shrinking drops the elements past the length
+a.length = 1
+a.length = 2000000000
+let shrunk = a.length + " " + a[1e9]
+
+let results = [far, names, keys.join(","), joined, shrunk, c.indexOf(8), c.slice(4999).join()]
+results.join(" | ")
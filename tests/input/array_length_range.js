@@ -0,0 +1,3 @@
+// An array can't be longer than 2^32 - 1 elements
+let a = []
+a.length = 4294967296
@@ -0,0 +1,21 @@
+let a = [1, 2, 3]
+let pushed = a.push(4, 5)
+let popped = a.pop()
+let shifted = a.shift()
+let unshifted = a.unshift(0)
+
+// a is now [0, 2, 3, 4], negative indices count from the end
+let sliced = a.slice(1, 0 - 1)
+let removed = a.splice(1, 2, "x", "y", "z")
+let ordered = [1, 2, 3]
+let reversed = ordered.reverse()
+let sparse = [1, undefined, 3]
+let joined = sparse.join("-")
+let both = a.concat(sliced, 9)
+
+let parts = [
+    pushed, popped, shifted, unshifted,
+    sliced.join(), removed.join(), a.join(), reversed.join(""), joined, both.join(""),
+    a.indexOf("y"), a.indexOf("missing"), a.indexOf(4, 0 - 1)
+]
+parts.join(" ")
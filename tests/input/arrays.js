@@ -0,0 +1,33 @@
+let a = [1, 2, 3]
+let empty = []
+let holes = [1, , 3]
+
+a[0] = 10
+let i = 1
+a[i + 1] = a[i] + a[2]
+
+// Writing past the end grows the array, reading past it is undefined
+a[4] = 7
+let missing = a[3]
+let past = a[10]
+
+let grown = a.length
+a.length = 2
+let shrunk = a.length
+
+// Keys are converted to strings, so these are the same element
+a["1"] = 20
+
+let checks = 0
+if (missing == undefined) {
+    checks = checks + 1
+}
+if (past == undefined) {
+    checks = checks + 1
+}
+if (holes[1] == undefined) {
+    checks = checks + 1
+}
+
+// 10 + 20 + 5 + 2 + 0 + 3 + 3
+a[0] + a[1] + grown + shrunk + empty.length + holes.length + checks
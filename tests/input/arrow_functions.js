@@ -0,0 +1,54 @@
+// Arrow functions take this and arguments from the function they are written in
+function map(list, callback) {
+    let result = []
+    for (let i = 0; i < list.length; i++) {
+        result.push(callback(list[i], i))
+    }
+    return result
+}
+
+let double = x => x * 2
+let add = (a, b) => a + b
+let describe = (name, count) => {
+    let plural = count === 1 ? "" : "s"
+    return count + " " + name + plural
+}
+let nothing = () => {}
+
+let scaler = {
+    factor: 10,
+    scale: function (list) {
+        return map(list, value => value * this.factor)
+    },
+}
+
+function countArguments() {
+    let inner = () => arguments.length
+    return inner(1, 2, 3, 4)
+}
+
+function makeCounter() {
+    let count = 0
+    return () => ++count
+}
+let counter = makeCounter()
+counter()
+counter()
+
+let results = [
+    double(21),
+    add(1, 2),
+    describe("apple", 1),
+    describe("pear", 3),
+    typeof nothing(),
+    scaler.scale([1, 2, 3]).join(","),
+    countArguments("a", "b"),
+    counter(),
+    map([1, 2], (x, i) => x + i).join(","),
+    typeof double,
+    double.hasOwnProperty("prototype"),
+    // Only a concise body is returned, a block body returns undefined without a return
+    typeof (() => { 5 })(),
+    typeof (function () { 6 })(),
+]
+results.join(" ")
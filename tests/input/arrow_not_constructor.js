@@ -0,0 +1,2 @@
+let make = () => {}
+new make()
@@ -0,0 +1,18 @@
+// The target of an assignment is located before the right-hand side is evaluated
+var i = 0
+var a = []
+a[i++] = i
+a[i++] += i
+a[i] = i++
+
+var log = []
+function step(name, value) {
+    log.push(name)
+    return value
+}
+var o = { count: 1 }
+step("object", o)[step("key", "count")] = step("value", 5)
+step("object", o)[step("key", "count")] *= step("value", 2)
+step("object", o).count++
+
+a.join(",") + " " + i + " " + o.count + " " + log.join(",")
@@ -0,0 +1,39 @@
+// A function declared in a block is also a var of the enclosing function, assigned when the
+// block runs
+function outer() {
+    let before = typeof inner
+    if (true) {
+        function inner() {
+            return "inner"
+        }
+    }
+    return before + " " + inner()
+}
+
+function shadowed(inner) {
+    {
+        function inner() {
+            return "block"
+        }
+    }
+    return inner
+}
+
+function lexical() {
+    let inner = "let"
+    {
+        function inner() {
+            return "block"
+        }
+    }
+    return inner
+}
+
+{
+    function atTop() {
+        return "top"
+    }
+}
+
+let results = [outer(), shadowed("parameter"), lexical(), atTop()]
+results.join(" ")
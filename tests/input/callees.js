@@ -0,0 +1,40 @@
+// Anything that evaluates to a function can be called, methods get the object they came from
+function makeAdder(n) {
+    return function (x) {
+        return x + n
+    }
+}
+
+function outer() {
+    let local = function () {
+        return "local"
+    }
+    return local()
+}
+
+let tools = {
+    name: "tools",
+    nested: {
+        name: "nested",
+        who: function () {
+            return this.name
+        },
+    },
+}
+let handlers = [
+    function () {
+        return "first"
+    },
+]
+
+let results = [
+    (function () {
+        return "immediate"
+    })(),
+    makeAdder(2)(3),
+    outer(),
+    tools.nested.who(),
+    tools["nested"]["who"](),
+    handlers[0](),
+]
+results.join(" ")
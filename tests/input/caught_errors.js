@@ -0,0 +1,34 @@
+let caught = 0
+
+try {
+    missing()
+} catch (e) {
+    if (e.name === "ReferenceError") {
+        caught = caught + 1
+    }
+}
+
+let o = {a: 1}
+try {
+    o.a()
+} catch (e) {
+    if (e.name === "TypeError") {
+        caught = caught + 10
+    }
+}
+
+// An uncaught exception still goes through every finally on its way out
+function nested() {
+    try {
+        try {
+            throw 1
+        } finally {
+            caught = caught + 100
+        }
+    } catch {
+        caught = caught + 1000
+    }
+}
+nested()
+
+caught // 1111
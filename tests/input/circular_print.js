@@ -0,0 +1,9 @@
+// Printing an object that refers back to itself marks the reference instead of following it
+let o = { name: "o" }
+o.self = o
+let a = [o]
+a.push(a)
+o.list = a
+console.log(o)
+console.log(a)
+o.self.self.name
@@ -0,0 +1,13 @@
+// Classes can only be constructed, and their constructor run by super, not called
+class A {
+    constructor(x) {
+        this.x = x
+    }
+}
+class B extends A {
+    constructor() {
+        super(1)
+    }
+}
+let b = new B()
+b.x + A()
@@ -0,0 +1,2 @@
+let notAClass = 5
+class Broken extends notAClass {}
@@ -0,0 +1,94 @@
+class Shape {
+    constructor(name) {
+        this.name = name
+    }
+
+    describe() {
+        let words = [this.name, "with area", this.area()]
+        return words.join(" ")
+    }
+
+    area() {
+        return 0
+    }
+
+    static unit() {
+        return new Square(1, 1)
+    }
+}
+
+class Rectangle extends Shape {
+    constructor(width, height) {
+        super("rectangle")
+        this.width = width
+        this.height = height
+    }
+
+    area() {
+        return this.width * this.height
+    }
+
+    get size() {
+        let sides = [this.width, this.height]
+        return sides.join("x")
+    }
+
+    set size(side) {
+        this.width = side
+        this.height = side
+    }
+}
+
+// No constructor, the arguments go to Rectangle's
+class Square extends Rectangle {
+    describe() {
+        let words = ["square:", super.describe()]
+        return words.join(" ")
+    }
+
+    static unit() {
+        let square = super.unit()
+        square.name = "unit"
+        return square
+    }
+}
+
+let r = new Rectangle(2, 3)
+let s = new Square(4, 4)
+let before = r.size
+r.size = 5
+let u = Square.unit()
+
+// Classes and constructor functions extend each other
+function Animal(name) {
+    this.name = name
+}
+let animal = Animal.prototype
+animal.speak = function () {
+    let words = [this.name, "makes a sound"]
+    return words.join(" ")
+}
+
+let Dog = class extends Animal {
+    speak() {
+        let words = [super.speak(), "woof"]
+        return words.join(", ")
+    }
+}
+let d = new Dog("Rex")
+
+let results = [
+    r.describe(),
+    before,
+    r.size,
+    s.describe(),
+    u.describe(),
+    d.speak(),
+    s instanceof Square,
+    s instanceof Rectangle,
+    s instanceof Shape,
+    d instanceof Animal,
+    r instanceof Square,
+    s.hasOwnProperty("area"),
+]
+results.join("; ")
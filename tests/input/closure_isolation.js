@@ -0,0 +1,10 @@
+function reads_caller_local() {
+    return secret
+}
+
+function caller() {
+    let secret = 1
+    return reads_caller_local()
+}
+
+caller()
@@ -0,0 +1,24 @@
+function make_counter(start) {
+    let count = start
+    return {
+        next: function () {
+            count++
+            return count
+        },
+    }
+}
+
+let first = make_counter(0)
+let second = make_counter(100)
+first.next()
+first.next()
+second.next()
+
+// The closure sees the variables it was created over, not the ones of its caller
+let count = 1000
+function call_next(counter) {
+    let count = 2000
+    return counter.next()
+}
+
+call_next(first) + second.next() // 3 + 102
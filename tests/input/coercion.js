@@ -0,0 +1,54 @@
+// Objects are converted to primitives with their valueOf and toString methods
+let money = {
+    valueOf: function () {
+        return 42
+    },
+}
+let named = {
+    toString: function () {
+        return "named"
+    },
+}
+let lookalike = {
+    valueOf: function () {
+        return 42
+    },
+}
+
+// Conditions accept any value, not only booleans
+let truthy = 0
+if ("yes") {
+    truthy++
+}
+if (0) {
+    truthy = 100
+}
+while (truthy < 3) {
+    truthy++
+}
+
+let results = [
+    "1" + 2,
+    1 + true,
+    1 == "1",
+    0 == false,
+    "" == 0,
+    undefined == 0,
+    "1" === 1,
+    money + 1,
+    "" + named,
+    money == 42,
+    money === money,
+    money === lookalike,
+    [1, [2, 3]] + "",
+    "10" < "9",
+    10 < "9",
+    undefined >= 1,
+    "0x10" * 1,
+    {} + "",
+    1e21 + "",
+    0.000001 + "",
+    1 / 0 + "",
+    truthy,
+]
+results.join(" ")
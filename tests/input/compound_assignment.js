@@ -0,0 +1,57 @@
+// Compound assignments and updates work on variables and on both kinds of properties. The
+// semicolons keep the parser from mistaking an assignment for the end of the previous line
+let calls = 0;
+function key(name) {
+    calls++;
+    return name;
+}
+
+let n = 10;
+n += 5;
+n -= 3;
+n *= 2;
+n /= 4;
+n %= 4;
+n **= 3;
+
+let bits = 1;
+bits <<= 4;
+bits |= 3;
+bits ^= 1;
+bits &= 6;
+bits >>= 1;
+let unsigned = -1;
+unsigned >>>= 28;
+
+let counter = { total: 1, hits: 0 };
+counter.total += 10;
+counter.total *= 2;
+counter.hits++;
+++counter.hits;
+let before = counter.hits--;
+
+let list = [1, 2, 3];
+list[key(0)] += 41;
+list[key(1)]++;
+let old = list[key(2)]--;
+
+let s = "a";
+s += 1;
+let text = "5";
+text++;
+
+let results = [
+    n,
+    bits,
+    counter.total,
+    counter.hits,
+    before,
+    list.join(","),
+    old,
+    calls,
+    s,
+    text,
+    typeof text,
+    unsigned,
+]
+results.join(" ");
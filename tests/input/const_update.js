@@ -0,0 +1,2 @@
+const count = 1
+count++
@@ -0,0 +1,26 @@
+// A constructor ending with an object assignment still makes an instance, only an explicit
+// return of an object replaces it
+function P() {
+    this.name = "p";
+    this.opts = {a: 1};
+}
+let p = new P();
+
+class C {
+    constructor() {
+        this.name = "c";
+        this.opts = {a: 2};
+    }
+}
+class D extends C {
+    constructor() {
+        super();
+        this.extra = {b: 3};
+    }
+}
+let c = new C();
+let d = new D();
+
+let checks = [p instanceof P, p.name, p.opts.a];
+checks.push(c instanceof C, c.name, c.opts.a, d instanceof D, d.extra.b);
+checks.join(" ");
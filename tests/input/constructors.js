@@ -0,0 +1,60 @@
+function Point(x, y) {
+    this.x = x
+    this.y = y
+}
+
+let proto = Point.prototype
+proto.sum = function () {
+    return this.x + this.y
+}
+
+let p = new Point(1, 2)
+let q = new Point(10, 20)
+
+// Returning an object replaces the instance, returning anything else doesn't
+function Replaced() {
+    this.kept = false
+    return {kept: true}
+}
+function Ignored() {
+    this.kept = true
+    return 5
+}
+let replaced = new Replaced()
+let ignored = new Ignored()
+
+// The arguments are evaluated before the callee is checked and its prototype read
+let notConstructor = 5;
+let order = ""
+try {
+    new notConstructor(order += "a")
+} catch (e) {
+    order += "E"
+}
+
+function Swapped() {}
+let replacement = {replaced: true}
+function swapPrototype() {
+    Swapped.prototype = replacement
+}
+let swapped = new Swapped(swapPrototype())
+
+let checks = [
+    p instanceof Point,
+    replaced instanceof Replaced,
+    p instanceof Object,
+    q instanceof Function,
+    Point instanceof Function,
+    proto.constructor instanceof Function,
+    replaced.kept,
+    ignored.kept,
+    order === "aE",
+    Object.getPrototypeOf(swapped) === replacement,
+]
+
+let arrays = [checks instanceof Array, checks instanceof Object]
+checks = checks.concat(arrays)
+
+// 3 + 30 = 33, followed by the checks
+checks.unshift(p.sum() + q.sum())
+checks.join(" ")
@@ -0,0 +1,19 @@
+function make_cycle() {
+    let parent = {child: 0}
+    let child = {parent: parent}
+    parent.child = child
+    return 0
+}
+
+// The function refers to the object through the scope it closes over
+function make_closure_cycle() {
+    let holder = {f: function () { return holder }}
+    return 0
+}
+
+make_cycle()
+make_cycle()
+make_closure_cycle()
+
+let kept = {self: 0}
+kept.self = kept
@@ -0,0 +1,8 @@
+// An array met again while it is being joined is joined as an empty string
+let a = [1]
+a.push(a)
+let b = [a, 2]
+a.push(b)
+
+let results = [a.join("-"), a.toString(), b.join(), "" + a]
+results.join(" | ")
@@ -0,0 +1,39 @@
+// A var is visible before its declaration, and everywhere in its function
+function hoisted() {
+    let early = x === undefined
+    var x = 1
+    if (true) {
+        var inner = 2
+    }
+    var x
+    let seen = [early, x, inner]
+    return seen.join(",")
+}
+
+// A let only lives in its block, and can shadow an outer one
+let shadowed = "outer"
+let fromBlock = "none"
+{
+    let shadowed = "inner"
+    fromBlock = shadowed
+}
+
+// Loops with a declaration get a variable of their own
+let total = 0
+for (const value of [1, 2, 3]) {
+    total = total + value
+}
+for (var i = 0; i < 3; i++) {
+    total = total + i
+}
+let count = 0
+for (let i = 0; i < 2; i++) {
+    count++
+}
+
+// Constants can hold objects whose contents still change
+const point = {x: 1}
+point.x = 5
+
+let results = [hoisted(), fromBlock, shadowed, total, i, count, point.x]
+results.join(" ")
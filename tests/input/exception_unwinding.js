@@ -0,0 +1,91 @@
+let log = [];
+
+// A throw deep in a call is caught by the try statement of a caller
+function thrower(depth) {
+    if (depth === 0) {
+        throw "deep";
+    }
+    return thrower(depth - 1);
+}
+try {
+    thrower(3);
+} catch (e) {
+    log.push(e);
+}
+
+// A return runs every finally block it leaves, innermost first
+function nested() {
+    try {
+        try {
+            return "returned";
+        } finally {
+            log.push("inner");
+        }
+    } finally {
+        log.push("outer");
+    }
+}
+log.push(nested());
+
+// Breaking out of a for-of loop through a finally block
+for (let x of [1, 2, 3]) {
+    try {
+        if (x === 2) {
+            break;
+        }
+    } finally {
+        log.push("of" + x);
+    }
+}
+
+// A handler left by a break no longer catches anything
+function stale() {
+    while (true) {
+        try {
+            break;
+        } catch (e) {
+            return "stale";
+        }
+    }
+    throw "after";
+}
+try {
+    stale();
+} catch (e) {
+    log.push(e);
+}
+
+// An exception thrown by a catch clause still runs the finally block
+try {
+    try {
+        throw 1;
+    } catch (e) {
+        throw e + 1;
+    } finally {
+        log.push("cleanup");
+    }
+} catch (e) {
+    log.push(e);
+}
+
+// A break in a finally block drops the exception
+for (let i = 0; i < 1; i++) {
+    try {
+        null.property;
+    } finally {
+        break;
+    }
+}
+
+// The finally block sees the bindings around the try statement, not those of the try block
+let x = "outer";
+for (let i = 0; i < 1; i++) {
+    try {
+        let x = "inner";
+        continue;
+    } finally {
+        log.push(x);
+    }
+}
+
+log.join(" ");
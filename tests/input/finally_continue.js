@@ -0,0 +1,14 @@
+let cleanups = 0
+let sum = 0
+for (let i = 0; i < 5; i++) {
+    try {
+        if (i === 1)
+            continue
+        sum = sum + i
+    } finally {
+        cleanups++
+    }
+}
+
+let results = [sum, cleanups]
+results.join(" ")
@@ -0,0 +1,82 @@
+class Point {
+    constructor(x, y) {
+        this.x = x
+        this.y = y
+    }
+
+    norm() {
+        return this.x * this.x + this.y * this.y
+    }
+}
+
+// Methods and constructor links aren't enumerated
+let p = new Point(1, 2)
+let pointKeys = []
+for (let key in p)
+    pointKeys.push(key)
+
+// Own keys come before inherited ones, which a shadowing property hides
+let base = { inherited: 1, shadowed: 2 }
+let derived = Object.create(base)
+derived.own = 3
+derived.shadowed = 4
+let chainKeys = []
+for (const key in derived)
+    chainKeys.push(key)
+
+let letters = ["a", "b", "c"]
+let indices = []
+for (var index in letters)
+    indices.push(index)
+
+let total = 0
+for (const n of [1, 2, 3, 4, 5, 6]) {
+    if (n === 2)
+        continue
+    if (n === 5)
+        break
+    total = total + n
+}
+
+let characters = []
+for (let c of "hey")
+    characters.push(c)
+
+// Anything with an iterator method can be iterated
+let range = {}
+range[Symbol.iterator] = function () {
+    let iterator = { current: 0 }
+    iterator.next = function () {
+        if (this.current < 3) {
+            this.current = this.current + 1
+            return { value: this.current, done: false }
+        }
+        return { value: 0, done: true }
+    }
+    return iterator
+}
+let squares = []
+for (const i of range)
+    squares.push(i * i)
+
+// Values can be assigned to existing variables, and a labeled continue skips to the next one
+let last = 0
+outer: for (last of letters) {
+    for (let i of range) {
+        if (i === 2)
+            continue outer
+        characters.push(last)
+    }
+}
+
+let results = [
+    pointKeys.join(","),
+    chainKeys.join(","),
+    indices.join(","),
+    index,
+    total,
+    characters.join(""),
+    squares.join(","),
+    last,
+]
+results.join(" ")
@@ -0,0 +1,4 @@
+let count = 0
+for (const n of 5)
+    count++
+count
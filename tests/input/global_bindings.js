@@ -0,0 +1,34 @@
+// Top level vars and functions are properties of the global object, from the start
+let before = typeof globalThis.g1 + ("g1" in globalThis);
+var g1 = 5;
+function g2() {
+    return 2;
+}
+{
+    function g3() {
+        return 3;
+    }
+}
+let g4 = 4;
+
+// Either one changes the other
+globalThis.g1 = globalThis.g1 + 1;
+g1 = g1 * 2;
+
+// Vars of functions stay in their scope
+function local() {
+    var inner = 1;
+    return inner;
+}
+local();
+
+let results = [
+    before,
+    globalThis.g1,
+    this.g1,
+    globalThis.g2(),
+    globalThis.g3(),
+    typeof globalThis.g4,
+    typeof globalThis.inner,
+];
+results.join(" ")
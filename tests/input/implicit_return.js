@@ -0,0 +1,7 @@
+// Running off the end of a function returns undefined, not the value of its last statement
+function last() {
+    let x = 5
+    x + 1
+}
+
+last()
@@ -0,0 +1,26 @@
+// Any truthy done ends the iteration, not only true
+let countdown = {};
+countdown[Symbol.iterator] = function () {
+    let remaining = 3;
+    return {
+        next: function () {
+            remaining = remaining - 1;
+            return { value: remaining, done: remaining < 0 ? 1 : 0 };
+        },
+    };
+};
+let values = [];
+for (const value of countdown)
+    values.push(value);
+
+// Array iterators keep their position to themselves
+let iterator = [1, 2, 3][Symbol.iterator]();
+let keys = [];
+for (let key in iterator)
+    keys.push(key);
+let hasArray = iterator.hasOwnProperty("%array");
+iterator["%index"] = 2;
+let first = iterator.next().value;
+
+let results = [values.join(","), keys.length, hasArray, first];
+results.join(" ")
@@ -0,0 +1,70 @@
+// Sum of the numbers below 10, except 3
+let sum = 0
+for (let i = 0; i < 10; i++) {
+    if (i === 3)
+        continue
+    sum = sum + i
+}
+
+let skipped = 0
+let n = 0
+while (n < 5) {
+    n++
+    if (n === 2) {
+        continue
+    }
+    skipped = skipped + n
+}
+
+let tries = 0
+do {
+    tries++
+    if (tries < 3)
+        continue
+    break
+} while (true)
+
+// Pairs with j < i, the labeled continue skips the rest of the inner loop
+let pairs = 0
+outer: for (let i = 0; i < 4; i++) {
+    for (let j = 0; j < 4; j++) {
+        if (j === i)
+            continue outer
+        pairs++
+    }
+}
+
+// The labeled break leaves both loops at once
+let found = 0
+search: while (true) {
+    let k = 0
+    while (k < 10) {
+        k++
+        if (k === 7) {
+            found = k
+            break search
+        }
+    }
+}
+
+// Labels can name any statement, only a break can leave one that isn't a loop
+let reached = 0
+block: {
+    reached = 1
+    if (reached === 1)
+        break block
+    reached = 2
+}
+
+function firstSquareAbove(limit) {
+    let i = 1
+    scan: while (true) {
+        if (i * i > limit)
+            return i
+        i++
+        continue scan
+    }
+}
+
+let results = [sum, skipped, tries, pairs, found, reached, firstSquareAbove(50)]
+results.join(" ")
@@ -0,0 +1,22 @@
+// Logical operators evaluate to one of their operands, and skip the right one when they can
+let calls = 0
+function count(value) {
+    calls++
+    return value
+}
+
+let results = [
+    0 || "fallback",
+    "first" || count("second"),
+    1 && "both",
+    "" && count("never"),
+    null || undefined,
+    0 && count(1),
+    count(2) && count(3),
+    calls,
+    calls > 2 ? "many" : "few",
+    0 ? count("yes") : "no",
+    calls,
+    true ? false ? 1 : 2 : 3,
+]
+results.join(" ")
@@ -0,0 +1,3 @@
+let name = "";
+name ||= "anonymous";
+name;
@@ -0,0 +1,31 @@
+// Closures created in the body of a for loop see the let bindings of their own iteration
+let fs = []
+for (let i = 0; i < 3; i++) {
+    fs.push(() => i)
+}
+
+let evens = []
+for (let i = 0; i < 6; i++) {
+    if (i % 2 === 1) {
+        continue
+    }
+    let square = i * i
+    evens.push(() => i + ":" + square)
+}
+
+let shared = []
+for (var j = 0; j < 3; j++) {
+    shared.push(() => j)
+}
+
+let results = []
+for (let k = 0; k < fs.length; k++) {
+    results.push(fs[k]())
+}
+for (let k = 0; k < evens.length; k++) {
+    results.push(evens[k]())
+}
+for (let k = 0; k < shared.length; k++) {
+    results.push(shared[k]())
+}
+results.join(" ")
@@ -0,0 +1,53 @@
+// Keeps polling until the value settles
+let polls = 0
+let value = 1
+while (value < 100) {
+    value = value * 3
+    polls++
+}
+
+// The body of a do-while runs before the first test
+let attempts = 0
+do {
+    attempts++
+} while (false)
+
+let retries = 0
+do {
+    retries++
+    if (retries === 3)
+        break
+} while (true)
+
+// A for loop without a test only ends with a break
+let spins = 0
+for (;;) {
+    spins++
+    if (spins === 4) {
+        break
+    }
+}
+
+// Returning from inside a loop leaves the function
+function firstPowerAbove(limit) {
+    let power = 1
+    while (true) {
+        power = power * 2
+        if (power > limit)
+            return power
+    }
+}
+
+let outer = 0
+let inner = 0
+while (outer < 3) {
+    let j = 0
+    do {
+        j++
+        inner++
+    } while (j < outer)
+    outer++
+}
+
+let results = [polls, value, attempts, retries, spins, firstPowerAbove(100), inner]
+results.join(" ")
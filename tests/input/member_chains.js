@@ -0,0 +1,40 @@
+// Member expressions work on any object expression, and read missing properties as undefined
+let config = { server: { ports: [80, 443], name: "main" } }
+function makeConfig() {
+    return config
+}
+function Counter() {
+    this.count = 0
+    this.step = { size: 2 }
+}
+Counter.prototype.grow = function () {
+    this.step.size++
+    return this.step.size
+}
+let counter = new Counter()
+counter.step.size += 1
+counter.grow()
+
+let key = "name"
+config.server.limits = { connections: 10 }
+config.server.limits.connections *= 3
+config.server["alias"] = "backup"
+config.server.ports[2] = 8080
+
+let results = [
+    config.server.name,
+    config.server[key],
+    makeConfig().server.ports[1],
+    config.server.ports.length,
+    config.server.ports[2],
+    config.server.limits.connections,
+    config.server.alias,
+    config.missing,
+    typeof config.server.missing,
+    counter.step.size,
+    [10, 20, 30][1],
+    { inline: "yes" }.inline,
+    delete config.server.alias,
+    config.server.alias,
+]
+results.join(" ")
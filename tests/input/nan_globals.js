@@ -0,0 +1,5 @@
+let results = [];
+results.push(NaN === NaN, isNaN(NaN), isNaN(0 / 0), isNaN(1));
+results.push(isNaN("abc"), isNaN("12"), isNaN(undefined), isNaN(null), isNaN());
+results.push(typeof NaN, 1 / 0 === Infinity, -Infinity < 0);
+results.join(" ");
@@ -0,0 +1,9 @@
+function outer() {
+    function inner() {
+        return 1
+    }
+    return inner()
+}
+
+outer()
+inner()
@@ -0,0 +1,51 @@
+// Declarations are hoisted, so they can be called before they appear
+function outer(n) {
+    let doubled = double(n)
+    function double(x) {
+        return x * 2
+    }
+    return doubled
+}
+
+// A nested function closes over the scope it is declared in
+function counter() {
+    let count = 0
+    function increment() {
+        count++
+        return count
+    }
+    increment()
+    return increment()
+}
+
+// Declarations in the same scope can call each other
+function parity(n) {
+    function isEven(k) {
+        if (k === 0) {
+            return true
+        }
+        return isOdd(k - 1)
+    }
+    function isOdd(k) {
+        if (k === 0) {
+            return false
+        }
+        return isEven(k - 1)
+    }
+    return isEven(n)
+}
+
+// A function declared in a block is bound there too
+let fromBlock = 0
+{
+    fromBlock = inBlock()
+    function inBlock() {
+        return 7
+    }
+}
+
+// Top level declarations end up on the global object
+let shared = globalThis.outer(1)
+
+let results = [outer(4), counter(), parity(7), fromBlock, shared]
+results.join(" ")
@@ -0,0 +1,9 @@
+let total = 0
+for (let i = 0; i < 100; ++i) {
+    for (let j = 0; j < 10; j++) {
+        if (j === 5)
+            break
+        total = total + j
+    }
+}
+total // 1000
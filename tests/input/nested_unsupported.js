@@ -0,0 +1,10 @@
+function area(shape) {
+    let scale = function () {
+        return () => {
+            with (shape) {
+                return width;
+            }
+        };
+    };
+    return scale()();
+}
@@ -0,0 +1,2 @@
+let notAFunction = {}
+new notAFunction()
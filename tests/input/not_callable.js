@@ -0,0 +1,2 @@
+let config = { server: { name: "main" } }
+config.server.start()
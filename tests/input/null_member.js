@@ -0,0 +1,3 @@
+// Only undefined and null have no properties at all
+let nothing = null
+"abc".length + nothing.length
@@ -0,0 +1,3 @@
+let options = {};
+options.retries ??= 3;
+options.retries;
@@ -0,0 +1,3 @@
+let settings = {};
+let width = settings.width ?? 80;
+width;
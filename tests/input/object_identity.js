@@ -0,0 +1,26 @@
+let original = {name: "a"}
+let alias = original
+let lookalike = {name: "a"}
+
+// Arrays find objects by reference, not by their contents
+let found = [lookalike, original]
+
+// Switch cases match the same object only
+let matched = "none"
+switch (alias) {
+    case lookalike:
+        matched = "lookalike"
+        break
+    case original:
+        matched = "original"
+}
+
+let results = [
+    original === alias,
+    original === lookalike,
+    original == alias,
+    original != lookalike,
+    found.indexOf(alias),
+    matched,
+]
+results.join(" ")
@@ -0,0 +1,39 @@
+// A tiny hash of character codes, the kind that relies on 32-bit integer arithmetic
+function hash(codes) {
+    let h = 5381
+    for (let i = 0; i < codes.length; i++) {
+        h = ((h << 5) + h) ^ codes[i]
+    }
+    return h >>> 0
+}
+
+let point = { x: 1 }
+function Point() {}
+let instance = new Point()
+
+let results = [
+    7 % 3,
+    -7 % 3,
+    5.5 % 2,
+    2 ** 10,
+    2 ** -1,
+    1 ** undefined,
+    1 << 31,
+    -16 >> 2,
+    -16 >>> 28,
+    -1 >>> 0,
+    1 << 33,
+    5 & 3,
+    5 | 3,
+    5 ^ 3,
+    4294967297 | 0,
+    "6" & "3",
+    "x" in point,
+    "toString" in point,
+    "y" in point,
+    0 in [1],
+    instance instanceof Point,
+    point instanceof Point,
+    hash([99, 104, 101, 99, 107, 115, 117, 109]),
+]
+results.join(" ")
@@ -0,0 +1,23 @@
+// Primitives have the properties of their type's prototype, strings their length and characters
+let word = "abc"
+let n = 5
+let results = [
+    "abc".length,
+    word[1],
+    word["2"],
+    typeof word[3],
+    (5).toString(),
+    n.toString() + 1,
+    true.toString(),
+    typeof true.toString,
+    false.valueOf(),
+    "x".valueOf(),
+    word.hasOwnProperty("length"),
+    "".length,
+]
+
+// Assigning to a property of a primitive doesn't keep it
+word.extra = 1;
+n.extra++
+results.push(typeof word.extra, typeof n.extra)
+results.join(" ")
@@ -0,0 +1,23 @@
+let o = {zebra: 1, apple: 2};
+o[10] = "ten";
+o[2] = "two";
+o.mango = 3;
+o.banana = 4;
+o[1] = "one";
+delete o.apple;
+o.apple = 5;
+o.zebra = 6;
+
+let keys = [];
+for (let key in o) {
+    keys.push(key);
+}
+
+// Inherited keys come after the object's own ones, each group in its own order
+let child = Object.create({inherited: 1, first: 2});
+child.own = 3;
+child.first = 4;
+for (let key in child) {
+    keys.push(key);
+}
+keys.join(" ");
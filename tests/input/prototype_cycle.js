@@ -0,0 +1,3 @@
+let a = {}
+let b = Object.create(a)
+Object.setPrototypeOf(a, b)
@@ -0,0 +1,47 @@
+let animal = {
+    describe: function () {
+        let words = [this.name, "says", this.sound]
+        return words.join(" ")
+    },
+    sound: "...",
+}
+
+let dog = Object.create(animal)
+dog.name = "Rex"
+dog.sound = "woof"
+
+// Nothing of its own to say, so the prototype's sound is used
+let rock = Object.create(animal)
+rock.name = "Rock"
+
+// Methods added to a prototype show up on everything inheriting from it
+let puppy = Object.create(dog)
+dog.wag = function () {
+    let words = [this.name, "wags"]
+    return words.join(" ")
+}
+puppy.name = "Bit"
+
+let parts = [dog.describe(), rock.describe(), puppy.describe(), puppy.wag()]
+
+// Own properties shadow inherited ones, without changing them
+puppy.sound = "yip"
+parts.push(puppy.describe(), dog.describe())
+
+// Plain objects, functions and arrays share the built-in prototypes
+let plain = {a: 1}
+function f() {}
+function g() {}
+let function_prototype = Object.getPrototypeOf(f)
+function_prototype.tag = "shared"
+let arrays = []
+parts.push(plain.hasOwnProperty("a"), plain.hasOwnProperty("hasOwnProperty"), g.tag)
+parts.push(arrays.hasOwnProperty("length"))
+
+// Prototypes can be swapped out after the fact
+let cat = {sound: "meow"}
+Object.setPrototypeOf(cat, animal)
+cat.name = "Tom"
+parts.push(cat.describe())
+
+parts.join(", ")
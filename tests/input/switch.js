@@ -0,0 +1,75 @@
+// The default can be anywhere, cases fall through until a break
+function steps(n) {
+    let taken = []
+    switch (n) {
+        case 1:
+            taken.push("one")
+        default:
+            taken.push("default")
+        case 2:
+            taken.push("two")
+            break
+        case 3:
+            return "three"
+    }
+    return taken.join("-")
+}
+
+// A continue goes on to the loop around the switch
+let evens = 0
+for (let i = 0; i < 6; i++) {
+    switch (i) {
+        case 1:
+        case 3:
+        case 5:
+            continue
+    }
+    evens++
+}
+
+// A labeled break can leave the loop around the switch
+let seen = 0
+outer: while (true) {
+    switch (seen) {
+        case 3:
+            break outer
+        default:
+            seen++
+    }
+}
+
+// The discriminant is evaluated once
+let calls = 0
+function next() {
+    calls++
+    return calls
+}
+let matched = "none"
+switch (next()) {
+    case 2:
+        matched = "two"
+        break
+    case 1:
+        matched = "one"
+}
+
+// Nothing runs without a match or a default
+let untouched = true
+switch (4) {
+    case 5:
+        untouched = false
+}
+
+let results = [
+    steps(1),
+    steps(2),
+    steps(3),
+    steps(9),
+    steps("1"),
+    evens,
+    seen,
+    calls,
+    matched,
+    untouched,
+]
+results.join(" ")
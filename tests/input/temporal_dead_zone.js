@@ -0,0 +1,7 @@
+function early() {
+    x = 2
+    let x = 1
+    return x
+}
+
+early()
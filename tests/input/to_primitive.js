@@ -0,0 +1,10 @@
+let stubborn = {
+    valueOf: function () {
+        return {}
+    },
+    toString: function () {
+        return {}
+    },
+}
+
+stubborn + 1
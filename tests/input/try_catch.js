@@ -0,0 +1,22 @@
+function thrower(x) {
+    throw x
+}
+
+let total = 0
+try {
+    thrower(1)
+    total = 100
+} catch (e) {
+    total = total + e
+} finally {
+    total = total + 10
+}
+
+// Finally runs without an exception too, and a catch isn't required
+try {
+    total = total + 1000
+} finally {
+    total = total + 10000
+}
+
+total // 11011
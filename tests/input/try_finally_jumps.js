@@ -0,0 +1,29 @@
+let count = 0
+
+function returns_early() {
+    try {
+        return 1
+    } finally {
+        count = count + 1
+    }
+    return 2
+}
+
+function finally_overrides() {
+    try {
+        throw 5
+    } finally {
+        return 3
+    }
+}
+
+for (let i = 0; i < 10; i++) {
+    try {
+        break
+    } finally {
+        count = count + 10
+    }
+}
+
+let returned = returns_early() + finally_overrides()
+count + returned // 15
@@ -0,0 +1,39 @@
+// typeof never throws, even for identifiers that were never declared
+let types = [
+    typeof 1,
+    typeof "s",
+    typeof true,
+    typeof undefined,
+    typeof null,
+    typeof {},
+    typeof [],
+    typeof function () {},
+    typeof neverDeclared,
+]
+
+let point = { x: 1, y: 2 }
+let deleted = delete point.x
+let list = [1, 2, 3]
+delete list[1]
+
+let results = [
+    types.join(","),
+    -"3",
+    +"4",
+    !0,
+    !"",
+    ~5,
+    ~-1,
+    void 0,
+    deleted,
+    point.hasOwnProperty("x"),
+    point.hasOwnProperty("y"),
+    list.join(","),
+    delete types,
+    null == undefined,
+    null === undefined,
+    null == 0,
+    null + 1,
+    Object.getPrototypeOf(Object.create(null)) === null,
+]
+results.join(" ")
@@ -0,0 +1,5 @@
+let x = {a: 1}
+
+with (x) {
+    a = 2
+}